@@ -0,0 +1,212 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Error, Expr, Fields, GenericArgument, Lit, LitStr, PathArguments, Type};
+
+
+/// The kind of `Args` specifier generated for a struct field.
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Required,
+    Optional,
+    Variadic,
+    Flag
+}
+
+
+/// Derives `parse()` and `parse_args()` for a struct with named fields.
+///
+/// Each field becomes an `Args` specifier, in the order the fields are
+/// declared:
+/// - `bool` fields are flags, named `--field-name`.
+/// - `Option<T>` fields are optional.
+/// - `Vec<T>` fields are variadic.
+/// - Any other type is required.
+///
+/// Values are converted with `FromStr`. A field's doc comment becomes its
+/// description, and `#[args(name = "-v")]` overrides its name.
+#[proc_macro_derive(Args, attributes(args))]
+pub fn derive_args(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into()
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(input, "Args can only be derived for structs with named fields"))
+        },
+        _ => return Err(Error::new_spanned(input, "Args can only be derived for structs"))
+    };
+
+    let mut specifiers = Vec::new();
+    let mut values = Vec::new();
+    let mut last_kind = Kind::Required;
+
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let (kind, inner) = kind_of(&field.ty);
+
+        let name = match name_of(&field.attrs)? {
+            Some(name) => name,
+            None if kind == Kind::Flag => format!("--{}", ident.to_string().replace('_', "-")),
+            None => ident.to_string()
+        };
+
+        match (&last_kind, &kind) {
+            (_, Kind::Flag) => (),
+            (Kind::Optional, Kind::Required) => return Err(Error::new_spanned(field, "required field declared after optional field")),
+            (Kind::Variadic, Kind::Required) => (),
+            (Kind::Variadic, _) => return Err(Error::new_spanned(field, "field declared after variadic field")),
+            _ => last_kind = kind
+        }
+
+        specifiers.push(match kind {
+            Kind::Required => quote! { args.required(#name); },
+            Kind::Optional => quote! { args.optional(#name); },
+            Kind::Variadic => quote! { args.variadic(#name); },
+            Kind::Flag => quote! { args.flag(#name); }
+        });
+
+        if let Some(description) = description_of(&field.attrs) {
+            specifiers.push(quote! { args.describe(#name, #description); });
+        }
+
+        let convert = quote! {
+            |value: String| value.parse::<#inner>().map_err(|_| {
+                ::args_helper::ArgsError::from(format!("argument '{}' has invalid value '{}'", #name, value))
+            })
+        };
+
+        values.push(match kind {
+            Kind::Required => quote! { #ident: (#convert)(args.get_unwrap(#name))? },
+            Kind::Optional => quote! { #ident: args.get(#name).map(#convert).transpose()? },
+            Kind::Variadic => quote! { #ident: args.get_all(#name).into_iter().map(#convert).collect::<Result<_, _>>()? },
+            Kind::Flag => quote! { #ident: args.has_flag(#name) }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics #ident #type_generics #where_clause {
+            /// Parses the program's command-line arguments.
+            pub fn parse() -> Result<Self, ::args_helper::ArgsError> {
+                Self::parse_args(&mut ::args_helper::Args::new())
+            }
+
+            /// Specifies every field on `args`, then parses them.
+            ///
+            /// `args` can be printed as the usage if an error is returned.
+            pub fn parse_args(args: &mut ::args_helper::Args) -> Result<Self, ::args_helper::ArgsError> {
+                #(#specifiers)*
+
+                args.check()?;
+
+                Ok(Self {
+                    #(#values),*
+                })
+            }
+        }
+    })
+}
+
+/// Gives the kind of specifier for a field's type, along with the type that
+/// each value is parsed into.
+fn kind_of(ty: &Type) -> (Kind, &Type) {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if segment.ident == "bool" {
+                return (Kind::Flag, ty);
+            }
+
+            if let PathArguments::AngleBracketed(generics) = &segment.arguments {
+                if let Some(GenericArgument::Type(inner)) = generics.args.first() {
+                    if segment.ident == "Option" {
+                        return (Kind::Optional, inner);
+                    }
+
+                    if segment.ident == "Vec" {
+                        return (Kind::Variadic, inner);
+                    }
+                }
+            }
+        }
+    }
+
+    (Kind::Required, ty)
+}
+
+/// Gives the name given by an `#[args(name = "...")]` attribute.
+fn name_of(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let mut name = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("args")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                name = Some(meta.value()?.parse::<LitStr>()?.value());
+                Ok(())
+            }
+            else {
+                Err(meta.error("unknown args attribute"))
+            }
+        })?;
+    }
+
+    Ok(name)
+}
+
+/// Gives the doc comment of a field, joined into a single line.
+fn description_of(attrs: &[Attribute]) -> Option<String> {
+    let lines: Vec<String> = attrs
+                                .iter()
+                                .filter(|attr| attr.path().is_ident("doc"))
+                                .filter_map(|attr| match &attr.meta.require_name_value().ok()?.value {
+                                    Expr::Lit(expr) => match &expr.lit {
+                                        Lit::Str(doc) => Some(doc.value().trim().to_string()),
+                                        _ => None
+                                    },
+                                    _ => None
+                                })
+                                .filter(|line| !line.is_empty())
+                                .collect();
+
+    if lines.is_empty() {
+        None
+    }
+    else {
+        Some(lines.join(" "))
+    }
+}
@@ -0,0 +1,120 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use args_helper::Args;
+use args_helper_derive::Args;
+
+#[derive(Args, Debug, PartialEq)]
+struct CopyFiles {
+    /// File to copy from.
+    source: String,
+    /// Number of copies.
+    count: u32,
+    /// Text to prepend
+    /// to every line.
+    prefix: Option<String>,
+    rest: Vec<i64>,
+    /// Print every file copied.
+    verbose: bool,
+    #[args(name = "-n")]
+    dry_run: bool
+}
+
+#[test]
+fn derive_parse_all() {
+    let mut args = Args::from(vec!["a.txt", "3", "> ", "-1", "2", "-n"]);
+    let copy = CopyFiles::parse_args(&mut args).unwrap();
+
+    assert_eq!(copy, CopyFiles {
+        source: String::from("a.txt"),
+        count: 3,
+        prefix: Some(String::from("> ")),
+        rest: vec![-1, 2],
+        verbose: false,
+        dry_run: true
+    });
+}
+
+#[test]
+fn derive_parse_missing_optional() {
+    let mut args = Args::from(vec!["a.txt", "3", "--verbose"]);
+    let copy = CopyFiles::parse_args(&mut args).unwrap();
+
+    assert_eq!(copy.prefix, None);
+    assert!(copy.rest.is_empty());
+    assert!(copy.verbose);
+}
+
+#[test]
+fn derive_parse_missing_required() {
+    let mut args = Args::from(vec!["a.txt"]);
+
+    assert!(CopyFiles::parse_args(&mut args).is_err());
+}
+
+#[test]
+fn derive_parse_invalid_value() {
+    let mut args = Args::from(vec!["a.txt", "three"]);
+    let error = CopyFiles::parse_args(&mut args).unwrap_err();
+
+    assert_eq!(error.get_problems(), ["argument 'count' has invalid value 'three'"]);
+}
+
+#[test]
+fn derive_descriptions() {
+    let mut args = Args::from(vec!["a.txt", "3"]);
+    let _ = CopyFiles::parse_args(&mut args);
+
+    assert_eq!(args.get_description("source"), Some(String::from("File to copy from.")));
+    assert_eq!(args.get_description("prefix"), Some(String::from("Text to prepend to every line.")));
+    assert_eq!(args.get_description("--verbose"), Some(String::from("Print every file copied.")));
+    assert_eq!(args.get_description("rest"), None);
+}
+
+#[test]
+fn derive_usage() {
+    let mut args = Args::from(Vec::<&str>::new());
+    let _ = CopyFiles::parse_args(&mut args);
+
+    assert!(format!("{}", args).starts_with("<source> <count> [prefix] [rest...] "));
+}
+
+#[derive(Args, Debug, PartialEq)]
+struct Move {
+    sources: Vec<String>,
+    dest: String
+}
+
+#[test]
+fn derive_required_after_variadic() {
+    let mut args = Args::from(vec!["a", "b", "dir"]);
+    let mv = Move::parse_args(&mut args).unwrap();
+
+    assert_eq!(mv, Move {
+        sources: vec![String::from("a"), String::from("b")],
+        dest: String::from("dir")
+    });
+}
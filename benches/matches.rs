@@ -0,0 +1,84 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+//! Compares matching command-lines with `Args::matches()`, which borrows
+//! every value, against building an `Args` for each command-line.
+//!
+//! Run with `cargo bench`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use args_helper::Args;
+
+
+const LINES: usize = 200_000;
+
+
+fn main() {
+    let lines: Vec<Vec<String>> = (0..LINES)
+                                    .map(|i| vec![format!("name{i}"), String::from("-v"), format!("--out=file{i}.txt"), format!("{}", i % 7), String::from("x"), String::from("y")])
+                                    .collect();
+    let lines: Vec<Vec<&str>> = lines.iter().map(|line| line.iter().map(String::as_str).collect()).collect();
+
+    let owned = time(|| {
+        for line in &lines {
+            let mut args = Args::from(line.clone());
+            specify(&mut args);
+
+            black_box((args.get("name"), args.get_all("rest"), args.has_flag("--verbose"), args.get_option("--out")));
+        }
+    });
+
+    let mut spec = Args::from(Vec::<&str>::new());
+    specify(&mut spec);
+
+    let borrowed = time(|| {
+        for line in &lines {
+            let matches = spec.matches(line);
+
+            black_box((matches.get("name"), matches.get_all("rest"), matches.has_flag("--verbose"), matches.get_option("--out")));
+        }
+    });
+
+    println!("{LINES} command-lines");
+    println!("owned:    {owned:?} ({:?} per line)", owned / LINES as u32);
+    println!("borrowed: {borrowed:?} ({:?} per line)", borrowed / LINES as u32);
+}
+
+fn specify(args: &mut Args) {
+    args.flag("-v|--verbose")
+        .option("-o|--out", "FILE")
+        .required("name")
+        .optional("level")
+        .variadic("rest");
+}
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
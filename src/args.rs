@@ -29,38 +29,233 @@
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::error::Error;
+use std::ffi::OsString;
 use std::fmt::{Debug, Display};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::mem;
+use std::ops::Range;
 use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use crate::provenance::Source;
+use crate::subcommand::Subcommand;
 
 
 enum Arg {
     Required(String),
-    Optional(String)
+    Optional(String),
+    Variadic(Vec<String>)
 }
 
 impl Arg {
-    /// Gives the inner value for `Required` and `Optional`.
+    /// Gives the inner value for `Required` and `Optional`, or the first
+    /// value for `Variadic`.
     /// 
     fn unwrap(&self) -> String {
         match self {
-            Self::Required(arg) | Self::Optional(arg) => arg.clone()
+            Self::Required(arg) | Self::Optional(arg) => arg.clone(),
+            Self::Variadic(args) => args[0].clone()
+        }
+    }
+
+    /// Gives every inner value.
+    fn values(&self) -> Vec<String> {
+        match self {
+            Self::Required(arg) | Self::Optional(arg) => vec![arg.clone()],
+            Self::Variadic(args) => args.clone()
+        }
+    }
+}
+
+
+/// A rule making one argument depend on another.
+enum Condition {
+    Requires(String, String),
+    RequiredIf(String, String, String),
+    RequiredUnless(String, String)
+}
+
+
+/// What was found on a command-line, for the checks shared by
+/// `Args::check()` and `Matches::check()`.
+pub(crate) trait Given {
+    /// Gives every flag and option found, as given.
+    fn flags(&self) -> Vec<&str>;
+
+    /// Gives every flag or option found for a key of all its names, e.g.
+    /// `--out=x` for `-o|--out`.
+    fn given_flags(&self, key: &str) -> Vec<&str>;
+
+    /// Gives every value of a field.
+    fn field_values(&self, name: &str) -> Vec<String>;
+
+    /// Gives the value of a field or option, or `None` if it has none.
+    fn value(&self, name: &str) -> Option<String>;
+
+    /// Indicates whether a field, flag or option was given, on the
+    /// command-line or wherever else values are taken from.
+    fn is_given(&self, name: &str) -> bool;
+}
+
+
+//---------------------------------------------------------------------------//
+
+
+/// Rules used to decide whether a command-line argument is a flag or a value.
+/// 
+/// By default, numeric-looking arguments such as `-5` and a lone `-` are
+/// treated as values rather than flags.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenRules {
+    /// Numeric-looking arguments are values, unless a matching flag has been
+    /// specified with `flag()`.
+    pub numbers_are_values: bool,
+
+    /// A lone `-` is a value, conventionally meaning stdin or stdout.
+    pub dash_is_value: bool
+}
+
+impl Default for TokenRules {
+    fn default() -> Self {
+        Self {
+            numbers_are_values: true,
+            dash_is_value: true
+        }
+    }
+}
+
+/// Gives every name of a flag or option from its example, e.g. `-o` and
+/// `--out` for `-o|--out=FILE`.
+pub(crate) fn flag_names(flag: &str) -> Vec<String> {
+    let names = flag.split_once('=').map_or(flag, |(names, _)| names);
+
+    names.split('|').map(String::from).collect()
+}
+
+/// Gives the on and off names of a switch, e.g. `--color` and `--no-color`
+/// for `--color`, or `+x` and `-x` for `+x`.
+pub(crate) fn switch_names(name: &str) -> (String, String) {
+    match name.strip_prefix("--") {
+        Some(long) => {
+            let long = long.strip_prefix("no-").unwrap_or(long);
+            (format!("--{long}"), format!("--no-{long}"))
+        },
+        None => {
+            let short = name.trim_start_matches(['-', '+']);
+            (format!("+{short}"), format!("-{short}"))
         }
     }
 }
 
+/// Reads the state of a switch from the environment or a config file.
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => None
+    }
+}
+
+/// Gives the name of a field from its example, e.g. `file` for `[file...]`.
+pub(crate) fn field_name(example: &str) -> &str {
+    example.trim_matches(['<', '>', '[', ']']).trim_end_matches("...")
+}
+
+/// Indicates whether an argument looks like a signed number, e.g. `-5`,
+/// `+1.5` or `-2e10`.
+fn is_numeric(arg: &str) -> bool {
+    let digits = arg.trim_start_matches(['-', '+']);
+
+    digits.len() + 1 == arg.len()
+        && digits.chars().next().is_some_and(|c| c.is_ascii_digit() || c == '.')
+        && digits.parse::<f64>().is_ok()
+}
+
+
+//---------------------------------------------------------------------------//
+
+
+/// Hints at the kind of value a field or option takes, for shell
+/// completion.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ValueHint {
+    File,
+    Directory
+}
+
+/// How many flags or options of a group may be given together.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroupKind {
+    AtMostOne,
+    ExactlyOne,
+    AtLeastOne
+}
+
+
+/// A specified field, as needed to generate completions and docs.
+pub(crate) struct FieldSpec<'a> {
+    pub(crate) name: &'a str,
+    pub(crate) is_optional: bool,
+    pub(crate) is_variadic: bool,
+    pub(crate) description: Option<&'a String>,
+    pub(crate) choices: Option<&'a Vec<String>>,
+    pub(crate) hint: Option<ValueHint>
+}
+
+/// A specified flag or option, as needed to generate completions and docs.
+pub(crate) struct FlagSpec<'a> {
+    pub(crate) names: Vec<String>,
+    pub(crate) value_name: Option<&'a str>,
+    pub(crate) description: Option<&'a String>,
+    pub(crate) choices: Option<&'a Vec<String>>,
+    pub(crate) hint: Option<ValueHint>
+}
+
 
 //---------------------------------------------------------------------------//
 
 
 pub struct Args {
     program_name: Option<String>,
-    command_line: Vec<String>,
+    pub(crate) tokens: Vec<String>,
+    rules: TokenRules,
+    abbreviate: bool,
+    pub(crate) reject_unknown: bool,
+    pub(crate) suggestion_threshold: f64,
+    pub(crate) command_line: Vec<String>,
     args: HashMap<String, Arg>,
-    arg_names: Vec<String>,
-    flags: Vec<String>,
+    pub(crate) arg_names: Vec<String>,
+    pub(crate) flags: Vec<String>,
     arg_count: usize,
-    possible_flags: HashSet<String>,
-    error_list: Vec<String>
+    pub(crate) possible_flags: Vec<String>,
+    pub(crate) flag_keys: HashMap<String, String>,
+    pub(crate) descriptions: HashMap<String, String>,
+    pub(crate) choices: HashMap<String, Vec<String>>,
+    pub(crate) hints: HashMap<String, ValueHint>,
+    pub(crate) about: Option<String>,
+    pub(crate) environment: Vec<(String, String)>,
+    pub(crate) exit_statuses: Vec<(i32, String)>,
+    pub(crate) defaults: HashMap<String, String>,
+    pub(crate) sensitive: HashSet<String>,
+    pub(crate) missing: Vec<String>,
+    pub(crate) groups: Vec<(String, GroupKind, Vec<String>)>,
+    pub(crate) switches: Vec<(String, Option<bool>)>,
+    pub(crate) variables: Vec<(String, String)>,
+    pub(crate) config: Vec<(String, String, String, usize)>,
+    pub(crate) secret_files: Vec<(String, String)>,
+    pub(crate) secret_reads: Mutex<HashMap<String, Result<String, String>>>,
+    pub(crate) subcommands: Vec<Subcommand>,
+    pub(crate) subcommand_at: Option<usize>,
+    pub(crate) multi_call: bool,
+    pub(crate) external: Option<OsString>,
+    pub(crate) external_files: OnceLock<Vec<String>>,
+    pub(crate) argv_offset: usize,
+    pub(crate) globals: Vec<String>,
+    pub(crate) outer_globals: Vec<(usize, String)>,
+    pub(crate) inner_globals: Vec<(usize, String)>,
+    conditions: Vec<Condition>,
+    pub(crate) error_list: Vec<String>
 }
 
 impl Args {
@@ -79,12 +274,43 @@ impl Args {
     fn init_empty() -> Self {
         Self {
             program_name: None,
+            tokens: Vec::new(),
+            rules: TokenRules::default(),
+            abbreviate: false,
+            reject_unknown: false,
+            suggestion_threshold: 0.7,
             command_line: Vec::new(),
             flags: Vec::new(),
             args: HashMap::new(),
             arg_names: Vec::new(),
             arg_count: 0,
-            possible_flags: HashSet::new(),
+            possible_flags: Vec::new(),
+            flag_keys: HashMap::new(),
+            descriptions: HashMap::new(),
+            choices: HashMap::new(),
+            hints: HashMap::new(),
+            about: None,
+            environment: Vec::new(),
+            exit_statuses: Vec::new(),
+            defaults: HashMap::new(),
+            sensitive: HashSet::new(),
+            missing: Vec::new(),
+            groups: Vec::new(),
+            switches: Vec::new(),
+            variables: Vec::new(),
+            config: Vec::new(),
+            secret_files: Vec::new(),
+            secret_reads: Mutex::new(HashMap::new()),
+            subcommands: Vec::new(),
+            subcommand_at: None,
+            multi_call: false,
+            external: None,
+            external_files: OnceLock::new(),
+            argv_offset: 0,
+            globals: Vec::new(),
+            outer_globals: Vec::new(),
+            inner_globals: Vec::new(),
+            conditions: Vec::new(),
             error_list: Vec::new()
         }
     }
@@ -97,6 +323,125 @@ impl Args {
         self.program_name = name.clone();
     }
 
+    pub fn get_token_rules(&self) -> TokenRules {
+        self.rules
+    }
+
+    /// Changes the rules used to tell flags apart from values.
+    /// 
+    /// Panics if any required or optional field has already been specified.
+    pub fn set_token_rules(&mut self, rules: TokenRules) {
+        if !self.arg_names.is_empty() {
+            panic!("token rules changed after arguments specified");
+        }
+
+        self.rules = rules;
+        self.tokenize();
+    }
+
+    /// Splits the raw command-line into flags and values, using the current
+    /// token rules. Anything after a subcommand belongs to the subcommand,
+    /// except for global flags, which are found at every level.
+    pub(crate) fn tokenize(&mut self) {
+        let end = self.subcommand_at.unwrap_or(self.tokens.len());
+        let (flags, command_line): (Vec<String>, Vec<String>) = self.tokens[..end]
+                                        .iter()
+                                        .cloned()
+                                        .partition(|arg| self.is_flag(arg));
+
+        let flags: Vec<String> = flags.into_iter()
+                                    .map(|flag| match self.abbreviation_candidates(&flag).as_slice() {
+                                        [name] => name.clone(),
+                                        _ => flag
+                                    })
+                                    .collect();
+
+        self.flags = self.outer_globals
+                        .iter()
+                        .map(|(_, flag)| flag.clone())
+                        .chain(flags)
+                        .chain(self.inner_globals.iter().map(|(_, flag)| flag.clone()))
+                        .collect();
+        self.command_line = command_line;
+    }
+
+    /// Gives every flag found on this level of the command-line, with its
+    /// index in `argv`, where the program name is at index 0.
+    pub(crate) fn own_flags(&self) -> Vec<(usize, String)> {
+        let end = self.subcommand_at.unwrap_or(self.tokens.len());
+
+        self.tokens[..end]
+            .iter()
+            .enumerate()
+            .filter(|(_, token)| self.is_flag(token))
+            .zip(&self.flags[self.outer_globals.len()..])
+            .map(|((index, _), flag)| (self.argv_offset + index + 1, flag.clone()))
+            .collect()
+    }
+
+    /// Allows long flags specified with `flag()` to be abbreviated on the
+    /// command-line, e.g. `--verb` for `--verbose`, as long as only one flag
+    /// starts with the abbreviation. A flag that is given in full is never
+    /// taken as an abbreviation.
+    /// 
+    /// An abbreviation of more than one flag is considered an error by
+    /// `check()`.
+    pub fn allow_abbreviations(&mut self) -> &mut Self {
+        self.abbreviate = true;
+        self.tokenize();
+
+        self
+    }
+
+    /// Gives every long flag that a command-line flag could be an
+    /// abbreviation of, if abbreviations are allowed.
+    pub(crate) fn abbreviation_candidates(&self, arg: &str) -> Vec<String> {
+        if !self.abbreviate || !arg.starts_with("--") || arg.len() < 3 || arg.contains('=') || self.is_declared_flag(arg) {
+            return Vec::new();
+        }
+
+        self.possible_flags
+            .iter()
+            .filter(|flag| !flag.contains('='))
+            .flat_map(|flag| flag_names(flag))
+            .filter(|name| name.starts_with("--") && name.starts_with(arg))
+            .collect()
+    }
+
+    /// Gives the key of the one flag that a command-line flag abbreviates, if
+    /// abbreviations are allowed, as `abbreviation_candidates()` does but
+    /// without allocating.
+    pub(crate) fn abbreviated_key(&self, arg: &str) -> Option<&str> {
+        if !self.abbreviate || !arg.starts_with("--") || arg.len() < 3 || arg.contains('=') || self.flag_keys.contains_key(arg) {
+            return None;
+        }
+
+        let mut keys = self.possible_flags
+                        .iter()
+                        .filter(|flag| !flag.contains('='))
+                        .filter(|flag| flag.split('|').any(|name| name.starts_with("--") && name.starts_with(arg)));
+
+        match (keys.next(), keys.next()) {
+            (Some(key), None) => Some(key),
+            _ => None
+        }
+    }
+
+    /// Indicates whether a command-line argument is a flag.
+    /// 
+    /// An empty argument is always a value.
+    pub(crate) fn is_flag(&self, arg: &str) -> bool {
+        if self.rules.dash_is_value && arg == "-" {
+            false
+        }
+        else if self.rules.numbers_are_values && is_numeric(arg) {
+            self.possible_flags.iter().any(|flag| flag_names(flag).iter().any(|name| name == arg))
+        }
+        else {
+            arg.starts_with(['-', '+'])
+        }
+    }
+
     /// Specifies the name of a required field.
     /// 
     /// Required fields take an argument in the order that they are given on
@@ -104,34 +449,31 @@ impl Args {
     /// the number of command-line arguments given, it is considered an error
     /// by `check()`.
     /// 
+    /// A required field can follow a variadic field, e.g. `<sources>...
+    /// <dest>`, in which case it takes one of the last arguments on the
+    /// command-line, as with `cp`.
+    /// 
     /// A name cannot be repeated by multiple fields.
     /// 
-    /// Panics if a required field is specified after an optional field.
+    /// Panics if a required field is specified after an optional field,
+    /// without a variadic field in between.
     pub fn required(&mut self, name: &str) -> &mut Self {
         let name = String::from(name);
 
-        if self.args.contains_key(&name) {
+        if self.has_field(&name) {
             panic!("required argument '{name}' specified twice");
         }
 
-        let optional_arg_count = self.args
-                                    .values()
-                                    .filter(|arg| matches!(arg, Arg::Optional(_)))
-                                    .count();
-
-        if optional_arg_count > 0 {
-            panic!("required argument '{name}' specified after optional argument");
+        if !self.subcommands.is_empty() || self.external.is_some() {
+            panic!("required argument '{name}' specified after subcommands");
         }
 
-        if self.arg_count >= self.command_line.len() {
-            self.error_list.push(format!("required argument '{name}' not found"));
-        }
-        else {
-            self.args.insert(name.clone(), Arg::Required(self.command_line[self.arg_count].clone()));
-            self.arg_count += 1;
+        if !self.has_variadic() && self.arg_names.iter().any(|example| example.starts_with('[')) {
+            panic!("required argument '{name}' specified after optional argument");
         }
 
         self.arg_names.push(format!("<{}>", name.clone()));
+        self.assign_fields();
         
         self
     }
@@ -145,44 +487,626 @@ impl Args {
     /// it is *not* considered an error by `check()`.
     /// 
     /// A name cannot be repeated by multiple fields.
+    /// 
+    /// Panics if an optional field is specified after a variadic field.
     pub fn optional(&mut self, name: &str) -> &mut Self {
         let name = String::from(name);
 
-        if self.args.contains_key(&String::from(&name)) {
+        if self.has_field(&name) {
             panic!("optional argument '{name}' specified twice");
         }
 
-        if self.arg_count < self.command_line.len() {
-            self.args.insert(name.clone(), Arg::Optional(self.command_line[self.arg_count].clone()));
-            self.arg_count += 1;
+        if !self.subcommands.is_empty() || self.external.is_some() {
+            panic!("optional argument '{name}' specified after subcommands");
+        }
+
+        if self.has_variadic() {
+            panic!("optional argument '{name}' specified after variadic argument");
         }
 
         self.arg_names.push(format!("[{}]", name.clone()));
+        self.assign_fields();
+
+        self
+    }
+
+    /// Specifies the name of a variadic field.
+    /// 
+    /// A variadic field takes every remaining argument on the command-line,
+    /// after any required and optional fields. It may take no arguments at
+    /// all, which is *not* considered an error by `check()`.
+    /// 
+    /// A name cannot be repeated by multiple fields.
+    /// 
+    /// Panics if more than one variadic field is specified.
+    pub fn variadic(&mut self, name: &str) -> &mut Self {
+        let name = String::from(name);
+
+        if self.has_field(&name) {
+            panic!("variadic argument '{name}' specified twice");
+        }
+
+        if !self.subcommands.is_empty() || self.external.is_some() {
+            panic!("variadic argument '{name}' specified after subcommands");
+        }
+
+        if self.has_variadic() {
+            panic!("variadic argument '{name}' specified after variadic argument");
+        }
+
+        self.arg_names.push(format!("[{}...]", name.clone()));
+        self.assign_fields();
+
+        self
+    }
+
+    /// Gives the range of command-line values taken by each field, in
+    /// order, or `None` for a field that takes none.
+    /// 
+    /// Fields are matched by arity: every required field takes a value
+    /// first, then optional fields take one each in order, and the variadic
+    /// field takes whatever is left. Values are then laid out in the order
+    /// of the fields.
+    pub(crate) fn field_ranges(&self, count: usize) -> Vec<Option<Range<usize>>> {
+        let required = self.arg_names.iter().filter(|example| example.starts_with('<')).count();
+        let mut spare = count.saturating_sub(required);
+        let mut next = 0;
+
+        self.arg_names
+            .iter()
+            .map(|example| {
+                let len = match (example.starts_with('<'), example.ends_with("...]")) {
+                    (true, _) => 1,
+                    (false, true) => mem::take(&mut spare),
+                    (false, false) => {
+                        let len = spare.min(1);
+                        spare -= len;
+                        len
+                    }
+                };
+                let len = len.min(count - next);
+                let range = (len > 0).then_some(next..next + len);
+
+                next += len;
+                range
+            })
+            .collect()
+    }
+
+    /// Gives every field its values from the command-line, by arity.
+    fn assign_fields(&mut self) {
+        let ranges = self.field_ranges(self.command_line.len());
+
+        self.args.clear();
+        self.missing.clear();
+        self.arg_count = 0;
+
+        for (example, range) in self.arg_names.iter().zip(ranges) {
+            let name = String::from(field_name(example));
+
+            match range {
+                Some(range) => {
+                    let arg = match (example.starts_with('<'), example.ends_with("...]")) {
+                        (true, _) => Arg::Required(self.command_line[range.start].clone()),
+                        (false, true) => Arg::Variadic(self.command_line[range.clone()].to_vec()),
+                        (false, false) => Arg::Optional(self.command_line[range.start].clone())
+                    };
+
+                    self.arg_count = self.arg_count.max(range.end);
+                    self.args.insert(name, arg);
+                },
+                None if example.starts_with('<') => self.missing.push(name),
+                None => ()
+            }
+        }
+    }
+
+    /// Indicates whether a field with the name has been specified.
+    fn has_field(&self, name: &str) -> bool {
+        self.arg_names.iter().any(|example| field_name(example) == name)
+    }
+
+    /// Indicates whether a variadic field has been specified.
+    fn has_variadic(&self) -> bool {
+        self.arg_names.iter().any(|name| name.ends_with("...]"))
+    }
+
+    /// Groups flags or options that must not be combined, or of which at
+    /// least one must be given.
+    /// 
+    /// `check()` considers it an error if the group's `kind` is not met,
+    /// naming every conflicting flag or option given. A group is shown as
+    /// `[--json|--yaml]` in the example command-line if at most one of it
+    /// can be given, otherwise as `(--json|--yaml)`.
+    /// 
+    /// Panics if any member has not been specified as a flag or option.
+    pub fn group(&mut self, name: &str, kind: GroupKind, members: &[&str]) -> &mut Self {
+        let members: Vec<String> = members
+                                    .iter()
+                                    .map(|member| self.flag_key(member).unwrap_or_else(|| panic!("no flag or option '{member}' specified")))
+                                    .collect();
+
+        self.groups.push((String::from(name), kind, members));
+
+        self
+    }
+
+    /// Gives every name of a specified flag or option joined by `|`, e.g.
+    /// `-o|--out`, or `None` if it has not been specified.
+    fn flag_key(&self, name: &str) -> Option<String> {
+        self.possible_flags
+            .iter()
+            .map(|flag| flag.split_once('=').map_or(flag.as_str(), |(names, _)| names))
+            .find(|names| names.split('|').any(|n| n == name))
+            .map(String::from)
+    }
+
+    /// Gives a problem for every value of a field or option that is not one
+    /// of its choices, with the closest choice as a suggestion.
+    /// 
+    /// Options are named by their first name, e.g. `-f` for `-f|--format`.
+    pub(crate) fn choice_problems(&self, given: &impl Given) -> Vec<(String, Option<String>)> {
+        let mut problems = Vec::new();
+        let fields = self.arg_names.iter().map(|example| String::from(field_name(example)));
+        let options = self.possible_flags.iter().filter_map(|flag| flag.split_once('=')).map(|(names, _)| String::from(names));
+
+        for key in fields.chain(options) {
+            let Some(choices) = self.choices.get(&key) else {
+                continue;
+            };
+
+            let name = key.split('|').next().unwrap_or_default();
+            let values = match self.has_field(&key) {
+                true => given.field_values(&key),
+                false => given.value(name).into_iter().collect()
+            };
+
+            // A suggestion would give away how close a sensitive value is.
+            for value in values.iter().filter(|value| !choices.contains(value)) {
+                problems.push((format!("argument '{name}' has invalid value '{}', expected one of: {}", self.redact(name, value), choices.join(", ")),
+                               self.suggest(value, choices.iter().map(String::as_str)).filter(|_| !self.is_sensitive(name))));
+            }
+        }
+
+        problems
+    }
+
+    /// Gives a problem for every switch's environment variable that is not
+    /// a boolean.
+    fn variable_problems(&self) -> Vec<String> {
+        self.variables
+            .iter()
+            .filter(|(key, _)| self.switches.iter().any(|(switch, _)| switch == key))
+            .filter_map(|(key, variable)| env::var(variable).ok().map(|value| (key, variable, value)))
+            .filter(|(_, _, value)| parse_bool(value).is_none())
+            .map(|(key, variable, value)| format!("environment variable '{variable}' has invalid value '{value}' for '{key}', expected true or false"))
+            .collect()
+    }
+
+    /// Gives a problem for every abbreviation of more than one flag.
+    pub(crate) fn abbreviation_problems(&self, given: &impl Given) -> Vec<String> {
+        given.flags()
+            .into_iter()
+            .filter_map(|flag| {
+                let candidates = self.abbreviation_candidates(flag);
+
+                (candidates.len() > 1).then(|| format!("ambiguous flag '{flag}', could be: {}", candidates.join(", ")))
+            })
+            .collect()
+    }
+
+    /// Gives a problem for every group whose kind is not met.
+    pub(crate) fn group_problems(&self, given: &impl Given) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (name, kind, members) in &self.groups {
+            let given_members = members.iter().filter(|member| !given.given_flags(member).is_empty()).count();
+            let names = members.join(", ");
+            let given = members.iter()
+                               .flat_map(|member| given.given_flags(member))
+                               .map(|flag| self.redact_flag(flag))
+                               .collect::<Vec<String>>()
+                               .join(", ");
+
+            match kind {
+                GroupKind::AtMostOne | GroupKind::ExactlyOne if given_members > 1 => {
+                    problems.push(format!("only one of {names} can be given for '{name}', found: {given}"));
+                },
+                GroupKind::ExactlyOne if given_members == 0 => {
+                    problems.push(format!("one of {names} is required for '{name}'"));
+                },
+                GroupKind::AtLeastOne if given_members == 0 => {
+                    problems.push(format!("at least one of {names} is required for '{name}'"));
+                },
+                _ => ()
+            }
+        }
+
+        problems
+    }
+
+    /// Makes a field, flag or option depend on another, e.g. `--key`
+    /// requires `--cert`.
+    /// 
+    /// `check()` considers it an error if `name` is given but `other` is not.
+    /// 
+    /// Panics if either has not been specified.
+    pub fn requires(&mut self, name: &str, other: &str) -> &mut Self {
+        self.expect_specified(&[name, other]);
+        self.conditions.push(Condition::Requires(String::from(name), String::from(other)));
+
+        self
+    }
+
+    /// Makes an optional field, flag or option required when another field
+    /// or option has a specific value.
+    /// 
+    /// `check()` considers it an error if `other` has `value` but `name` is
+    /// not given.
+    /// 
+    /// Panics if either has not been specified.
+    pub fn required_if(&mut self, name: &str, other: &str, value: &str) -> &mut Self {
+        self.expect_specified(&[name, other]);
+        self.conditions.push(Condition::RequiredIf(String::from(name), String::from(other), String::from(value)));
+
+        self
+    }
+
+    /// Makes a field, flag or option required unless another is given, e.g.
+    /// `<target>` is required unless `--all` is given.
+    /// 
+    /// `check()` considers it an error if neither `name` nor `other` is
+    /// given. A required field need not be given when `other` is.
+    /// 
+    /// Panics if either has not been specified.
+    pub fn required_unless(&mut self, name: &str, other: &str) -> &mut Self {
+        self.expect_specified(&[name, other]);
+        self.conditions.push(Condition::RequiredUnless(String::from(name), String::from(other)));
+
+        self
+    }
+
+    /// Panics if any of the named fields, flags, options or switches has not
+    /// been specified.
+    fn expect_specified(&self, names: &[&str]) {
+        for name in names {
+            if !self.has_field(name) && !self.is_declared_flag(name) {
+                panic!("no argument, flag or option '{name}' specified");
+            }
+        }
+    }
+
+    /// Indicates whether a required field has been specified.
+    fn is_required_field(&self, name: &str) -> bool {
+        self.arg_names.iter().any(|example| example.starts_with('<') && field_name(example) == name)
+    }
+
+    /// Gives a problem, in plain language, for every unmet condition.
+    pub(crate) fn condition_problems(&self, given: &impl Given) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for condition in &self.conditions {
+            match condition {
+                Condition::Requires(name, other) if given.is_given(name) && !given.is_given(other) => {
+                    problems.push(format!("'{name}' was given, so '{other}' is required as well"));
+                },
+                Condition::RequiredIf(name, other, value) if !given.is_given(name) && given.value(other).as_ref() == Some(value) => {
+                    problems.push(format!("'{name}' is required when '{other}' is '{value}'"));
+                },
+                // A required field that is missing is already reported as such.
+                Condition::RequiredUnless(name, other) if !given.is_given(name) && !given.is_given(other) && !self.is_required_field(name) => {
+                    problems.push(format!("'{name}' is required unless '{other}' is given"));
+                },
+                _ => ()
+            }
+        }
+
+        problems
+    }
+
+    /// Gives a description to a named field or flag.
+    /// 
+    /// The field or flag does not have to have been specified yet.
+    pub fn describe(&mut self, name: &str, description: &str) -> &mut Self {
+        self.descriptions.insert(String::from(name), String::from(description));
+
+        self
+    }
+
+    /// Gives a description of the program itself.
+    /// 
+    /// The first line is used as a summary, e.g. in the NAME section of a
+    /// man page.
+    pub fn about(&mut self, about: &str) -> &mut Self {
+        self.about = Some(String::from(about));
+
+        self
+    }
+
+    /// Describes an environment variable used by the program.
+    pub fn environment(&mut self, variable: &str, description: &str) -> &mut Self {
+        self.environment.push((String::from(variable), String::from(description)));
+
+        self
+    }
+
+    /// Describes an exit status of the program.
+    pub fn exit_status(&mut self, status: i32, description: &str) -> &mut Self {
+        self.exit_statuses.push((status, String::from(description)));
+
+        self
+    }
+
+    /// Restricts the values of a named field or option.
+    /// 
+    /// It is considered an error by `check()` if any other value is given.
+    /// 
+    /// Panics if no field or option with the name has been specified.
+    pub fn choices(&mut self, name: &str, choices: &[&str]) -> &mut Self {
+        let key = self.spec_key(name);
+        let choices: Vec<String> = choices.iter().map(|choice| String::from(*choice)).collect();
+
+        self.choices.insert(key, choices);
+
+        self
+    }
+
+    /// Gives a default value to a named field or option.
+    /// 
+    /// The default is used by `get()` and `get_option()` when an optional
+    /// field or an option is not found. A required field is still considered
+    /// an error by `check()` when not found, but its default is offered when
+    /// prompting for it.
+    /// 
+    /// Panics if no field or option with the name has been specified.
+    pub fn default_value(&mut self, name: &str, value: &str) -> &mut Self {
+        let key = self.spec_key(name);
+        self.defaults.insert(key, String::from(value));
 
         self
     }
 
+    /// Takes the value of a named field, option or switch from an environment
+    /// variable, when it is not found on the command-line.
+    /// 
+    /// The variable takes precedence over config files and defaults. A
+    /// switch's variable is read as with config files, e.g. `true` or `no`.
+    /// 
+    /// Panics if no field, option or switch with the name has been
+    /// specified.
+    pub fn env(&mut self, name: &str, variable: &str) -> &mut Self {
+        let key = self.layer_key(name);
+        self.variables.push((key, String::from(variable)));
+
+        self
+    }
+
+    /// Marks a named field or option as sensitive, e.g. a password, so that
+    /// it is not echoed when prompted for, and its value is shown as
+    /// `<redacted>` wherever it is formatted, e.g. by `explain()`,
+    /// `to_shell_string()` and the problems given by `check()`.
+    /// 
+    /// See also `sensitive_file()`.
+    /// 
+    /// Panics if no field or option with the name has been specified.
+    pub fn sensitive(&mut self, name: &str) -> &mut Self {
+        let key = self.spec_key(name);
+        self.sensitive.insert(key);
+
+        self
+    }
+
+    /// Hints at the kind of value a named field or option takes, for shell
+    /// completion.
+    /// 
+    /// Panics if no field or option with the name has been specified.
+    pub fn hint(&mut self, name: &str, hint: ValueHint) -> &mut Self {
+        let key = self.spec_key(name);
+        self.hints.insert(key, hint);
+
+        self
+    }
+
+    /// Gives the key used for a named field or option, which is every name
+    /// of an option joined by `|`, e.g. `-o|--out`.
+    /// 
+    /// Panics if no field or option with the name has been specified.
+    pub(crate) fn spec_key(&self, name: &str) -> String {
+        if self.arg_names.iter().any(|example| field_name(example) == name) {
+            return String::from(name);
+        }
+
+        self.possible_flags
+            .iter()
+            .filter_map(|flag| flag.split_once('='))
+            .find(|(names, _)| names.split('|').any(|n| n == name))
+            .map(|(names, _)| String::from(names))
+            .unwrap_or_else(|| panic!("no argument or option '{name}' specified"))
+    }
+
+    /// Gives the key used for a named field, option or switch that can take
+    /// its value from the environment or a config file.
+    /// 
+    /// Panics if no field, option or switch with the name has been
+    /// specified.
+    pub(crate) fn layer_key(&self, name: &str) -> String {
+        let (on, off) = switch_names(name);
+
+        match self.switches.iter().find(|(switch, _)| *switch == on || *switch == off) {
+            Some((switch, _)) => switch.clone(),
+            None => self.spec_key(name)
+        }
+    }
+
+    /// Gives the value of a field, option or switch from the environment or
+    /// a config file, by its key, or `None` if neither has one.
+    /// 
+    /// Later config files take precedence over earlier ones.
+    pub(crate) fn layered(&self, key: &str) -> Option<String> {
+        self.layered_source(key).map(|(value, _)| value)
+    }
+
+    /// Gives the value of a field, option or switch from a file named on the
+    /// command-line, the environment or a config file, along with where it
+    /// came from.
+    pub(crate) fn layered_source(&self, key: &str) -> Option<(String, Source)> {
+        self.secret(key)
+            .or_else(|| self.variables
+                            .iter()
+                            .filter(|(name, _)| name == key)
+                            .find_map(|(_, variable)| env::var(variable).ok().map(|value| (value, Source::Environment(variable.clone())))))
+            .or_else(|| self.config
+                            .iter()
+                            .rev()
+                            .find(|(name, ..)| name == key)
+                            .map(|(_, value, path, line)| (value.clone(), Source::Config(path.clone(), *line))))
+    }
+
+    /// Gives the default of a named optional field, or of an option by its
+    /// key, or `None` if it has none.
+    pub(crate) fn get_default(&self, key: &str) -> Option<&str> {
+        let is_field = self.arg_names.iter().any(|example| field_name(example) == key);
+        let is_optional = self.arg_names.iter().any(|example| example.starts_with('[') && field_name(example) == key);
+
+        match !is_field || is_optional {
+            true => self.defaults.get(key).map(String::as_str),
+            false => None
+        }
+    }
+
+    /// Gives the description of a named field or flag, or `None` if it has
+    /// not been described.
+    pub fn get_description(&self, name: &str) -> Option<String> {
+        self.descriptions.get(name).cloned()
+    }
+
     /// Specifies the name of an optional flag.
     /// 
     /// Flags are any command-line argument that begins with `-` or `+`.
     /// Panics if, when specifying the name of a flag, the qualifier is
     /// not given.
     /// 
+    /// Several names for the same flag can be given, separated by `|`, e.g.
+    /// `-v|--verbose`. Any of the names can then be given to `has_flag()`.
+    /// 
     /// This method does not need to be called for a flag to be found by
     /// the `has_flag()` method. This method exists to build an example
     /// command-line for `Display`.
     /// 
+    /// A numeric-looking flag, such as `-1`, is otherwise treated as a value
+    /// by the default token rules. It must be specified before any required
+    /// or optional field, so that it is not taken as a field's value.
+    /// 
     /// Panics if a flag name is repeated.
     pub fn flag(&mut self, name: &str) -> &mut Self {
-        if self.possible_flags.contains(&String::from(name)) {
-            panic!("flag '{}' specified twice", name);
+        self.add_possible_flag(name, String::from(name));
+
+        if self.rules.numbers_are_values && flag_names(name).iter().any(|name| is_numeric(name)) {
+            if !self.arg_names.is_empty() {
+                panic!("numeric flag '{name}' specified after arguments");
+            }
+
+            self.tokenize();
+        }
+        else if self.abbreviate {
+            self.tokenize();
         }
 
-        self.possible_flags.insert(String::from(name));
+        self
+    }
+
+    /// Specifies the name of an option, which is a flag that takes a value,
+    /// e.g. `--out=FILE`.
+    /// 
+    /// The value must be joined to the option by `=` on the command-line.
+    /// `value_name` is only used to build an example command-line for
+    /// `Display`. Several names can be given, as with `flag()`.
+    /// 
+    /// This method does not need to be called for an option to be found by
+    /// the `get_option()` method.
+    /// 
+    /// Panics if an option name is repeated.
+    pub fn option(&mut self, name: &str, value_name: &str) -> &mut Self {
+        self.add_possible_flag(name, format!("{name}={value_name}"));
+
+        self
+    }
+
+    /// Specifies a switch, which can be turned on or off, e.g. `--color`
+    /// and `--no-color`, or `+x` and `-x`.
+    /// 
+    /// `get_bool()` gives the last of these found on the command-line, or
+    /// `default` if neither was found. The switch is shown as `--[no-]color`
+    /// or `+x|-x` in the example command-line.
+    /// 
+    /// Panics if either name has already been specified.
+    pub fn switch(&mut self, name: &str, default: Option<bool>) -> &mut Self {
+        let (on, off) = switch_names(name);
+
+        self.check_flag_name(&on);
+        self.check_flag_name(&off);
+        self.flag_keys.insert(on.clone(), on.clone());
+        self.flag_keys.insert(off.clone(), off);
+        self.switches.push((on, default));
+
+        if self.abbreviate {
+            self.tokenize();
+        }
 
         self
     }
 
+    /// Adds a flag or option to the example command-line.
+    /// 
+    /// Panics if any of its names have already been specified.
+    fn add_possible_flag(&mut self, name: &str, flag: String) {
+        for name in flag_names(name) {
+            self.check_flag_name(&name);
+        }
+
+        self.push_possible_flag(flag);
+    }
+
+    /// Adds a flag or option, indexing the key of each of its names.
+    pub(crate) fn push_possible_flag(&mut self, flag: String) {
+        let key = flag.split_once('=').map_or(flag.as_str(), |(names, _)| names);
+
+        for name in key.split('|') {
+            self.flag_keys.insert(String::from(name), String::from(key));
+        }
+
+        self.possible_flags.push(flag);
+    }
+
+    /// Panics if a flag, option or switch name has already been specified.
+    fn check_flag_name(&self, name: &str) {
+        if self.is_declared_flag(name) {
+            panic!("flag '{}' specified twice", name);
+        }
+    }
+
+    /// Indicates whether a name belongs to a specified flag, option or
+    /// switch.
+    pub(crate) fn is_declared_flag(&self, name: &str) -> bool {
+        let is_flag = self.possible_flags.iter().any(|flag| flag_names(flag).iter().any(|n| n == name));
+        let is_switch = self.switches.iter().any(|(on, _)| {
+            let (on, off) = switch_names(on);
+            on == name || off == name
+        });
+
+        is_flag || is_switch
+    }
+
+    /// Gives every name of a specified flag or option, including `name`
+    /// itself.
+    pub(crate) fn flag_aliases(&self, name: &str) -> Vec<String> {
+        self.possible_flags
+            .iter()
+            .map(|flag| flag_names(flag))
+            .find(|names| names.iter().any(|n| n == name))
+            .unwrap_or_else(|| vec![String::from(name)])
+    }
+
     pub fn flag_required_or(&self, _either: &str, _or: &str) -> &mut Self {
         todo!()
     }
@@ -190,40 +1114,146 @@ impl Args {
     /// Returns an error if any required fields are not found.
     /// 
     pub fn check(&self) -> ArgsResult<&Self> {
-        if self.error_list.is_empty() {
+        let problems = self.problems();
+
+        if problems.is_empty() {
             Ok(self)
         }
         else {
-            Err(ArgsError::from(&self.error_list))
+            Err(ArgsError::with_suggestions(problems))
+        }
+    }
+
+    /// Gives every problem found by `check()`, each with what may have been
+    /// meant instead, if anything.
+    pub(crate) fn problems(&self) -> Vec<(String, Option<String>)> {
+        let mut problems: Vec<String> = self.missing_fields()
+                                            .iter()
+                                            .map(|name| format!("required argument '{name}' not found"))
+                                            .collect();
+        problems.extend(self.error_list.iter().cloned());
+        problems.extend(self.secret_problems(self));
+
+        // Problems that may come with a suggestion of what was meant.
+        let mut problems: Vec<(String, Option<String>)> = problems.into_iter().map(|problem| (problem, None)).collect();
+        problems.extend(self.choice_problems(self));
+        problems.extend(self.unknown_flag_problems(self));
+        problems.extend(self.abbreviation_problems(self).into_iter().map(|problem| (problem, None)));
+        problems.extend(self.variable_problems().into_iter().map(|problem| (problem, None)));
+        problems.extend(self.group_problems(self).into_iter().map(|problem| (problem, None)));
+        problems.extend(self.condition_problems(self).into_iter().map(|problem| (problem, None)));
+
+        // A global flag is checked both here and by the subcommand.
+        for problem in self.subcommand_problems() {
+            if !problems.contains(&problem) {
+                problems.push(problem);
+            }
         }
+
+        problems
+    }
+
+    /// Gives every required field that was not found on the command-line,
+    /// in the environment or in a config file.
+    pub(crate) fn missing_fields(&self) -> Vec<String> {
+        self.missing
+            .iter()
+            .filter(|name| self.layered(name).is_none())
+            .filter(|name| !self.is_excused(name, self))
+            .cloned()
+            .collect()
+    }
+
+    /// Indicates whether a required field need not be given, because it is
+    /// only required unless another field, flag or option is given, and
+    /// that one was.
+    pub(crate) fn is_excused(&self, name: &str, given: &impl Given) -> bool {
+        self.conditions
+            .iter()
+            .any(|condition| matches!(condition, Condition::RequiredUnless(field, other) if field == name && given.is_given(other)))
+    }
+
+    /// Indicates whether a field was given a value, on the command-line or
+    /// from a prompt.
+    pub(crate) fn has_value(&self, name: &str) -> bool {
+        self.args.contains_key(name)
+    }
+
+    /// Gives a value to a missing required field, e.g. from a prompt.
+    pub(crate) fn supply(&mut self, name: &str, value: String) {
+        self.missing.retain(|missing| missing != name);
+        self.args.insert(String::from(name), Arg::Required(value));
     }
 
     /// Gives the value of a named argument, or `None` if it was not
     /// found.
+    /// 
+    /// For a variadic field, gives the first value. A field that was not
+    /// found takes its value from the environment or a config file, if any,
+    /// and then an optional or variadic field takes its default.
     pub fn get(&self, name: &str) -> Option<String> {
         let name = String::from(name);
 
         if self.args.contains_key(&name) {
             Some(self.args[&name].unwrap())
         }
+        else if self.arg_names.iter().any(|example| field_name(example) == name) {
+            self.layered(&name).or_else(|| self.arg_names
+                                                .iter()
+                                                .any(|example| example.starts_with('[') && field_name(example) == name)
+                                                .then(|| self.defaults.get(&name).cloned())
+                                                .flatten())
+        }
         else {
             None
         }
     }
 
+    /// Gives every value of a named argument, which is empty if it was not
+    /// found.
+    /// 
+    /// Mostly useful for variadic fields. A field that was not found takes
+    /// a single value from the environment or a config file, if any.
+    pub fn get_all(&self, name: &str) -> Vec<String> {
+        match self.args.get(name) {
+            Some(arg) => arg.values(),
+            None if self.arg_names.iter().any(|example| field_name(example) == name) => {
+                self.layered(name).into_iter().collect()
+            },
+            None => Vec::new()
+        }
+    }
+
     /// Gives an unwrapped value for a named argumment.
     /// 
     /// Panics if the argument value was not found. Safe to use for
     /// required arguments **after** `check()` gas been called and
     /// not returned `Err()`.
     pub fn get_unwrap(&self, name: &str) -> String {
-        let name = String::from(name);
+        match self.get(name) {
+            Some(value) => value,
+            None => panic!("argument '{}' not found", name)
+        }
+    }
 
-        if !self.args.contains_key(&name) {
-            panic!("argument '{}' not found", name);
+    /// Opens the file named by an argument for reading.
+    /// 
+    /// Falls back to stdin if the argument is `-` or was not found.
+    pub fn get_input(&self, name: &str) -> io::Result<Box<dyn BufRead>> {
+        match self.get(name) {
+            Some(path) if path != "-" => Ok(Box::new(BufReader::new(File::open(path)?))),
+            _ => Ok(Box::new(io::stdin().lock()))
         }
+    }
 
-        self.args[&name].unwrap()
+    /// Creates the file named by an argument for writing.
+    /// 
+    /// Falls back to stdout if the argument is `-` or was not found.
+    pub fn get_output(&self, name: &str) -> io::Result<Box<dyn Write>> {
+        match self.get(name) {
+            Some(path) if path != "-" => Ok(Box::new(File::create(path)?)),
+            _ => Ok(Box::new(io::stdout().lock()))
+        }
     }
 
     /// Indicates whether a specific flag was found on the command-line
@@ -231,13 +1261,166 @@ impl Args {
     /// The flag does not have to have been previously specified with the
     /// `flag()` method.
     pub fn has_flag(&self, name: &str) -> bool {
-        self.flags.contains(&String::from(name))
+        let aliases = self.flag_aliases(name);
+
+        self.flags.iter().any(|flag| aliases.contains(flag))
+    }
+
+    /// Gives the value of a named option, or `None` if it was not found on
+    /// the command-line.
+    /// 
+    /// If the option is given more than once, the last value is used. If it
+    /// is not given at all, its value is taken from the environment or a
+    /// config file, and then its default, if any. The option does not have
+    /// to have been previously specified with the `option()` method.
+    pub fn get_option(&self, name: &str) -> Option<String> {
+        let aliases = self.flag_aliases(name);
+
+        self.flags
+            .iter()
+            .rev()
+            .filter_map(|flag| flag.split_once('='))
+            .find(|(flag, _)| aliases.iter().any(|alias| alias == flag))
+            .map(|(_, value)| String::from(value))
+            .or_else(|| self.layered(&aliases.join("|")))
+            .or_else(|| self.defaults.get(&aliases.join("|")).cloned())
+    }
+
+    /// Gives the state of a switch, which is the last of its on or off names
+    /// found on the command-line, e.g. `Some(false)` for `--no-color`.
+    /// 
+    /// If neither was found, its state is taken from the environment or a
+    /// config file, and then its default. Either name can be given. The
+    /// switch does not have to have been previously specified with the
+    /// `switch()` method.
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        let (on, off) = switch_names(name);
+
+        self.flags
+            .iter()
+            .rev()
+            .find_map(|flag| match flag {
+                flag if *flag == on => Some(true),
+                flag if *flag == off => Some(false),
+                _ => None
+            })
+            .or_else(|| self.layered(&on).and_then(|value| parse_bool(&value)))
+            .or_else(|| self.switches.iter().find(|(name, _)| *name == on).and_then(|(_, default)| *default))
+    }
+
+    /// Gives every specified field, in order.
+    pub(crate) fn field_specs(&self) -> Vec<FieldSpec<'_>> {
+        self.arg_names
+            .iter()
+            .map(|example| {
+                let name = field_name(example);
+
+                FieldSpec {
+                    name,
+                    is_optional: example.starts_with('['),
+                    is_variadic: example.ends_with("...]"),
+                    description: self.descriptions.get(name),
+                    choices: self.choices.get(name),
+                    hint: self.hints.get(name).copied()
+                }
+            })
+            .collect()
+    }
+
+    /// Gives every specified flag and option, in order.
+    pub(crate) fn flag_specs(&self) -> Vec<FlagSpec<'_>> {
+        self.possible_flags
+            .iter()
+            .map(|flag| {
+                let key = flag.split_once('=').map_or(flag.as_str(), |(names, _)| names);
+                let names = flag_names(flag);
+                let description = self.descriptions
+                                    .get(key)
+                                    .or_else(|| names.iter().find_map(|name| self.descriptions.get(name)));
+
+                FlagSpec {
+                    value_name: flag.split_once('=').map(|(_, value_name)| value_name),
+                    description,
+                    choices: self.choices.get(key),
+                    hint: self.hints.get(key).copied(),
+                    names
+                }
+            })
+            .chain(self.switches.iter().map(|(on, _)| {
+                let (on, off) = switch_names(on);
+
+                FlagSpec {
+                    value_name: None,
+                    description: self.descriptions.get(&on).or_else(|| self.descriptions.get(&off)),
+                    choices: None,
+                    hint: None,
+                    names: vec![on, off]
+                }
+            }))
+            .collect()
+    }
+
+    /// Gives the example command-line, without the program name.
+    /// 
+    /// Used by `Display`.
+    pub(crate) fn synopsis(&self) -> Vec<String> {
+        let mut synopsis = self.arg_names.clone();
+        let mut shown = HashSet::new();
+
+        if !self.subcommands.is_empty() || self.external.is_some() {
+            synopsis.push(String::from("<command>"));
+        }
+
+        for flag in &self.possible_flags {
+            let key = flag.split_once('=').map_or(flag.as_str(), |(names, _)| names);
+
+            match self.groups.iter().find(|(_, _, members)| members.iter().any(|member| member == key)) {
+                Some((name, kind, members)) => {
+                    if shown.insert(name) {
+                        let members: Vec<&String> = members
+                                                        .iter()
+                                                        .filter_map(|member| self.possible_flags.iter().find(|flag| flag_names(flag).join("|") == *member))
+                                                        .collect();
+                        let members = members.iter().map(|flag| flag.as_str()).collect::<Vec<&str>>().join("|");
+
+                        match kind {
+                            GroupKind::AtMostOne => synopsis.push(format!("[{members}]")),
+                            _ => synopsis.push(format!("({members})"))
+                        }
+                    }
+                },
+                None => synopsis.push(format!("[{flag}]"))
+            }
+        }
+
+        for (on, _) in &self.switches {
+            match on.strip_prefix("--") {
+                Some(long) => synopsis.push(format!("[--[no-]{long}]")),
+                None => synopsis.push(format!("[{on}|{}]", switch_names(on).1))
+            }
+        }
+
+        synopsis
+    }
+
+    /// Gives the file name of the `program_name` field.
+    /// 
+    /// Panics if there is no valid program name, as `what` needs one.
+    pub(crate) fn expect_program_name(&self, what: &str) -> String {
+        let mut program = String::new();
+        self.use_program_name(|name| program = String::from(name));
+
+        if program.is_empty() {
+            panic!("{what} needs a program name");
+        }
+
+        program
     }
 
     /// Helper function to use the `program_name` field, if it is valid.
     /// 
     /// Used by `Debug` and `Display`.
-    fn use_program_name(&self, mut f: impl FnMut(&str)) {
+    pub(crate) fn use_program_name(&self, mut f: impl FnMut(&str)) {
         if let Some(program_name) = &self.program_name.clone() {
             if let Some(path) = Path::new::<String>(program_name).file_name() {
                 f(path.to_str().unwrap_or_default());
@@ -246,6 +1429,55 @@ impl Args {
     }
 }
 
+impl Default for Args {
+    /// Gives an empty `Self`, without reading the process's command-line,
+    /// unlike `new()`.
+    fn default() -> Self {
+        Self::from(Vec::<String>::new())
+    }
+}
+
+impl Given for Args {
+    fn flags(&self) -> Vec<&str> {
+        self.flags.iter().map(String::as_str).collect()
+    }
+
+    fn given_flags(&self, key: &str) -> Vec<&str> {
+        let names: Vec<&str> = key.split('|').collect();
+
+        self.flags
+            .iter()
+            .map(String::as_str)
+            .filter(|flag| names.contains(&flag.split_once('=').map_or(*flag, |(flag, _)| flag)))
+            .collect()
+    }
+
+    /// Values not found on the command-line are taken from the environment
+    /// or a config file.
+    fn field_values(&self, name: &str) -> Vec<String> {
+        self.args
+            .get(name)
+            .map(Arg::values)
+            .or_else(|| self.layered(name).map(|value| vec![value]))
+            .unwrap_or_default()
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        self.get(name).or_else(|| self.get_option(name))
+    }
+
+    /// Values from a file named for a sensitive option, the environment or
+    /// a config file count as given.
+    fn is_given(&self, name: &str) -> bool {
+        let key = self.flag_aliases(name).join("|");
+
+        self.args.contains_key(name)
+            || self.has_flag(name)
+            || !self.given_flags(&key).is_empty()
+            || self.layered(&key).is_some()
+    }
+}
+
 impl Debug for Args {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut f = f.debug_struct("Args");
@@ -255,6 +1487,7 @@ impl Debug for Args {
         for name in &self.arg_names {
             let arg_type = match name.chars().nth(0).unwrap_or_default() {
                 '<' => "required",
+                '[' if name.ends_with("...]") => "variadic",
                 '[' => "optional",
                 _ => "arg"
             };
@@ -273,12 +1506,8 @@ impl Display for Args {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.use_program_name(|n| {let _ = f.write_fmt(format_args!("{} ", n));});
 
-        for name in &self.arg_names {
-            let _ = f.write_fmt(format_args!("{} ", name));
-        }
-
-        for flag in &self.possible_flags {
-            let _ = f.write_fmt(format_args!("[{}] ", flag));
+        for item in self.synopsis() {
+            let _ = f.write_fmt(format_args!("{} ", item));
         }
 
         Ok(())
@@ -289,14 +1518,8 @@ impl From<Vec<&str>> for Args {
     fn from(args: Vec<&str>) -> Self {
         let mut me = Self::init_empty();
         
-        for arg in args {
-            if ['-', '+'].contains(&arg.chars().nth(0).unwrap()) {
-                me.flags.push(String::from(arg));
-            }
-            else {
-                me.command_line.push(String::from(arg));
-            }
-        }
+        me.tokens = args.iter().map(|arg| String::from(*arg)).collect();
+        me.tokenize();
         
         me
     }
@@ -306,14 +1529,8 @@ impl From<Vec<String>> for Args {
     fn from(args: Vec<String>) -> Self {
         let mut me = Self::init_empty();
         
-        for arg in args {
-            if ['-', '+'].contains(&arg.chars().nth(0).unwrap()) {
-                me.flags.push(arg);
-            }
-            else {
-                me.command_line.push(arg);
-            }
-        }
+        me.tokens = args;
+        me.tokenize();
         
         me
     }
@@ -323,22 +1540,48 @@ impl From<Vec<String>> for Args {
 //---------------------------------------------------------------------------//
 
 
-type ArgsResult<T> = Result<T, ArgsError>;
+pub(crate) type ArgsResult<T> = Result<T, ArgsError>;
 
 pub struct ArgsError {
-    problems: Vec<String>
+    problems: Vec<String>,
+    suggestions: Vec<Option<String>>
 }
 
 impl ArgsError {
     fn new(problem: String) -> Self {
         Self {
-            problems: Vec::from(&[problem])
+            problems: Vec::from(&[problem]),
+            suggestions: vec![None]
+        }
+    }
+
+    /// Creates a new `Self` from problems, each with what may have been
+    /// meant instead, if anything. The suggestion is added to the problem's
+    /// text, e.g. `unknown command 'biuld', did you mean 'build'?`.
+    pub(crate) fn with_suggestions(problems: Vec<(String, Option<String>)>) -> Self {
+        let (problems, suggestions) = problems
+                                        .into_iter()
+                                        .map(|(problem, suggestion)| match &suggestion {
+                                            Some(suggestion) => (format!("{problem}, did you mean '{suggestion}'?"), Some(suggestion.clone())),
+                                            None => (problem, None)
+                                        })
+                                        .unzip();
+
+        Self {
+            problems,
+            suggestions
         }
     }
 
     pub fn get_problems(&self) -> &[String] {
         &self.problems
     }
+
+    /// Gives what may have been meant for each problem, in the same order
+    /// as `get_problems()`, e.g. `build` for an unknown command `biuld`.
+    pub fn get_suggestions(&self) -> &[Option<String>] {
+        &self.suggestions
+    }
 }
 
 impl Debug for ArgsError {
@@ -386,7 +1629,8 @@ impl From<String> for ArgsError {
 impl From<&[String]> for ArgsError {
     fn from(value: &[String]) -> Self {
         ArgsError {
-            problems: Vec::from(value)
+            problems: Vec::from(value),
+            suggestions: vec![None; value.len()]
         }
     }
 }
@@ -394,7 +1638,8 @@ impl From<&[String]> for ArgsError {
 impl From<&Vec<String>> for ArgsError {
     fn from(value: &Vec<String>) -> Self {
         ArgsError {
-            problems: value.clone()
+            problems: value.clone(),
+            suggestions: vec![None; value.len()]
         }
     }
 }
@@ -0,0 +1,468 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::fmt::Write;
+
+use crate::args::{Args, ValueHint};
+use crate::shell::shell_quote;
+
+
+/// A shell that completion scripts can be generated for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish
+}
+
+
+//---------------------------------------------------------------------------//
+
+
+impl Args {
+    /// Gives a completion script for a shell, covering every specified
+    /// field, flag and option.
+    ///
+    /// Choices and value hints are used to complete values, and descriptions
+    /// are shown where the shell supports them.
+    ///
+    /// Panics if there is no program name.
+    pub fn completion(&self, shell: Shell) -> String {
+        let program = self.expect_program_name("completion");
+
+        match shell {
+            Shell::Bash => self.bash_completion(&program),
+            Shell::Zsh => self.zsh_completion(&program),
+            Shell::Fish => self.fish_completion(&program)
+        }
+    }
+
+    fn bash_completion(&self, program: &str) -> String {
+        let function = bash_name(program);
+        let mut script = String::new();
+
+        let _ = writeln!(script, "# bash completion for {program}");
+        self.bash_function(&function, &mut script);
+        let _ = writeln!(script);
+        let _ = writeln!(script, "complete -F _{function} {program}");
+
+        script
+    }
+
+    /// Writes the bash function completing this command, and then one for
+    /// each of its subcommands. Each function takes the index of the first
+    /// word after its command.
+    fn bash_function(&self, function: &str, script: &mut String) {
+        let _ = writeln!(script);
+        let _ = writeln!(script, "_{function}() {{");
+        let _ = writeln!(script, "    local line=\"${{COMP_LINE:0:COMP_POINT}}\"");
+        let _ = writeln!(script, "    local cur=\"${{line##*[[:space:]]}}\"");
+
+        if self.subcommands.is_empty() {
+            let _ = writeln!(script, "    local words word n=0");
+            let _ = writeln!(script, "    read -ra words <<< \"${{line%\"$cur\"}}\"");
+            let _ = writeln!(script, "    for word in \"${{words[@]:${{1:-1}}}}\"; do");
+            let _ = writeln!(script, "        [[ \"$word\" == [-+]?* ]] || n=$((n + 1))");
+            let _ = writeln!(script, "    done");
+        }
+        else {
+            let _ = writeln!(script, "    local words word i=${{1:-1}} n=0");
+            let _ = writeln!(script, "    read -ra words <<< \"${{line%\"$cur\"}}\"");
+            let _ = writeln!(script, "    for word in \"${{words[@]:i}}\"; do");
+            let _ = writeln!(script, "        i=$((i + 1))");
+            let _ = writeln!(script, "        [[ \"$word\" == [-+]?* ]] && continue");
+            let _ = writeln!(script, "        case \"$word\" in");
+
+            for subcommand in &self.subcommands {
+                let _ = writeln!(script, "            {})", subcommand.names.join("|"));
+                let _ = writeln!(script, "                _{function}_{} \"$i\"", bash_name(&subcommand.names[0]));
+                let _ = writeln!(script, "                return");
+                let _ = writeln!(script, "                ;;");
+            }
+
+            let _ = writeln!(script, "        esac");
+            let _ = writeln!(script, "        n=$((n + 1))");
+            let _ = writeln!(script, "    done");
+        }
+
+        let _ = writeln!(script);
+        let _ = writeln!(script, "    case \"$cur\" in");
+
+        let flags = self.flag_specs();
+        let mut words = Vec::new();
+
+        for flag in &flags {
+            match flag.value_name {
+                Some(_) => {
+                    words.extend(flag.names.iter().map(|name| format!("{name}=")));
+
+                    if let Some(reply) = bash_reply(flag.choices, flag.hint, "${cur#*=}") {
+                        let pattern: Vec<String> = flag.names.iter().map(|name| format!("{name}=*")).collect();
+
+                        let _ = writeln!(script, "        {})", pattern.join("|"));
+                        let _ = writeln!(script, "            {reply}");
+                        let _ = writeln!(script, "            return");
+                        let _ = writeln!(script, "            ;;");
+                    }
+                },
+                None => words.extend(flag.names.iter().cloned())
+            }
+        }
+
+        let _ = writeln!(script, "        [-+]*)");
+        let _ = writeln!(script, "            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", words.join(" "));
+        let _ = writeln!(script, "            [[ \"${{COMPREPLY[0]}}\" == *= ]] && compopt -o nospace");
+        let _ = writeln!(script, "            return");
+        let _ = writeln!(script, "            ;;");
+        let _ = writeln!(script, "    esac");
+        let _ = writeln!(script);
+        let _ = writeln!(script, "    case \"$n\" in");
+
+        if !self.subcommands.is_empty() {
+            let _ = writeln!(script, "        0)");
+            let _ = writeln!(script, "            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))", self.subcommand_names().join(" "));
+            let _ = writeln!(script, "            ;;");
+        }
+
+        for (i, field) in self.field_specs().iter().enumerate() {
+            if let Some(reply) = bash_reply(field.choices, field.hint, "$cur") {
+                let pattern = if field.is_variadic { String::from("*") } else { i.to_string() };
+
+                let _ = writeln!(script, "        {pattern})");
+                let _ = writeln!(script, "            {reply}");
+                let _ = writeln!(script, "            ;;");
+            }
+        }
+
+        let _ = writeln!(script, "    esac");
+        let _ = writeln!(script, "}}");
+
+        for subcommand in &self.subcommands {
+            subcommand.args.bash_function(&format!("{function}_{}", bash_name(&subcommand.names[0])), script);
+        }
+    }
+
+    fn zsh_completion(&self, program: &str) -> String {
+        let mut script = String::new();
+
+        let _ = writeln!(script, "#compdef {program}");
+        let _ = writeln!(script);
+
+        if self.subcommands.is_empty() {
+            let _ = write!(script, "_arguments");
+
+            for spec in self.zsh_specs() {
+                let _ = write!(script, " \\\n    {spec}");
+            }
+
+            let _ = writeln!(script);
+        }
+        else {
+            let function = bash_name(program);
+
+            self.zsh_function(&function, &mut script);
+            let _ = writeln!(script, "_{function} \"$@\"");
+        }
+
+        script
+    }
+
+    /// Writes the zsh function completing this command, and then one for
+    /// each of its subcommands.
+    fn zsh_function(&self, function: &str, script: &mut String) {
+        let _ = writeln!(script, "_{function}() {{");
+
+        if self.subcommands.is_empty() {
+            let _ = write!(script, "    _arguments");
+
+            for spec in self.zsh_specs() {
+                let _ = write!(script, " \\\n        {spec}");
+            }
+
+            let _ = writeln!(script);
+        }
+        else {
+            let _ = writeln!(script, "    local state line");
+            let _ = write!(script, "    _arguments -C");
+
+            for spec in self.zsh_specs() {
+                let _ = write!(script, " \\\n        {spec}");
+            }
+
+            let _ = writeln!(script, " \\\n        '1:command:->command' \\\n        '*::argument:->argument'");
+            let _ = writeln!(script);
+            let _ = writeln!(script, "    case $state in");
+            let _ = writeln!(script, "        command)");
+            let _ = write!(script, "            _values command");
+
+            for (name, description) in self.subcommand_completions() {
+                let _ = write!(script, " {}", zsh_quote(&format!("{}[{}]", zsh_escape(&name, "[]:"), zsh_escape(&description, "[]"))));
+            }
+
+            let _ = writeln!(script);
+            let _ = writeln!(script, "            ;;");
+            let _ = writeln!(script, "        argument)");
+            let _ = writeln!(script, "            case $line[1] in");
+
+            for subcommand in &self.subcommands {
+                let _ = writeln!(script, "                {}) _{function}_{} ;;", subcommand.names.join("|"), bash_name(&subcommand.names[0]));
+            }
+
+            let _ = writeln!(script, "            esac");
+            let _ = writeln!(script, "            ;;");
+            let _ = writeln!(script, "    esac");
+        }
+
+        let _ = writeln!(script, "}}");
+        let _ = writeln!(script);
+
+        for subcommand in &self.subcommands {
+            subcommand.args.zsh_function(&format!("{function}_{}", bash_name(&subcommand.names[0])), script);
+        }
+    }
+
+    /// Gives the quoted `_arguments` specs for every flag, option and field.
+    fn zsh_specs(&self) -> Vec<String> {
+        let mut specs = Vec::new();
+
+        for flag in self.flag_specs() {
+            let exclusions = flag.names.join(" ");
+            let description = flag.description.map(|d| format!("[{}]", zsh_escape(d, "[]"))).unwrap_or_default();
+
+            for name in &flag.names {
+                let spec = match flag.value_name {
+                    Some(value_name) => format!("({exclusions}){name}=-{description}:{}:{}", zsh_escape(value_name, ":"), zsh_action(flag.choices, flag.hint)),
+                    None => format!("({exclusions}){name}{description}")
+                };
+
+                specs.push(zsh_quote(&spec));
+            }
+        }
+
+        for field in self.field_specs() {
+            let prefix = match (field.is_variadic, field.is_optional) {
+                (true, _) => "*:",
+                (false, true) => "::",
+                (false, false) => ":"
+            };
+            let message = zsh_escape(field.description.map_or(field.name, |d| d.as_str()), ":");
+            let spec = format!("{prefix}{message}:{}", zsh_action(field.choices, field.hint));
+
+            specs.push(zsh_quote(&spec));
+        }
+
+        specs
+    }
+
+    fn fish_completion(&self, program: &str) -> String {
+        let mut script = String::new();
+
+        let _ = writeln!(script, "# fish completion for {program}");
+        let _ = writeln!(script);
+
+        self.fish_lines(program, None, 0, &mut script);
+
+        script
+    }
+
+    /// Writes the fish completions for this command, and then for each of
+    /// its subcommands. `condition` is when this command applies, and
+    /// `depth` is how many subcommands deep it is.
+    fn fish_lines(&self, program: &str, condition: Option<&str>, depth: usize, script: &mut String) {
+        let prefix = match condition {
+            Some(condition) => format!("complete -c {program} -n {}", fish_quote(condition)),
+            None => format!("complete -c {program}")
+        };
+
+        for flag in self.flag_specs() {
+            let mut line = prefix.clone();
+
+            for name in &flag.names {
+                if let Some(long) = name.strip_prefix("--") {
+                    let _ = write!(line, " -l {}", fish_quote(long));
+                }
+                else if let Some(short) = name.strip_prefix('-').filter(|short| short.chars().count() == 1) {
+                    let _ = write!(line, " -s {}", fish_quote(short));
+                }
+                else if let Some(old) = name.strip_prefix('-') {
+                    let _ = write!(line, " -o {}", fish_quote(old));
+                }
+                else {
+                    let _ = write!(line, " -a {}", fish_quote(name));
+                }
+            }
+
+            if flag.value_name.is_some() {
+                line.push_str(&fish_action(flag.choices, flag.hint));
+            }
+
+            if let Some(description) = flag.description {
+                let _ = write!(line, " -d {}", fish_quote(description));
+            }
+
+            let _ = writeln!(script, "{line}");
+        }
+
+        for (i, field) in self.field_specs().iter().enumerate() {
+            let test = if field.is_variadic { "-ge" } else { "-eq" };
+            let mut field_condition = format!("test (count (string match -rv -- \"^[-+].\" (commandline -opc))) {test} {}", i + depth + 1);
+            let action = match (field.choices, field.hint) {
+                (Some(choices), _) => format!("-f -a {}", fish_words(choices)),
+                (None, Some(ValueHint::File)) => String::from("-F"),
+                (None, Some(ValueHint::Directory)) => String::from("-f -a '(__fish_complete_directories)'"),
+                (None, None) => continue
+            };
+
+            if let Some(condition) = condition {
+                field_condition = format!("{condition}; and {field_condition}");
+            }
+
+            let mut line = format!("complete -c {program} -n {} {action}", fish_quote(&field_condition));
+
+            let _ = write!(line, " -d {}", fish_quote(field.description.map_or(field.name, |d| d.as_str())));
+
+            let _ = writeln!(script, "{line}");
+        }
+
+        if self.subcommands.is_empty() {
+            return;
+        }
+
+        let names_condition = match condition {
+            Some(condition) => format!("{condition}; and not __fish_seen_subcommand_from {}", self.subcommand_names().join(" ")),
+            None => String::from("__fish_use_subcommand")
+        };
+
+        for (name, description) in self.subcommand_completions() {
+            let _ = writeln!(script, "complete -c {program} -n {} -f -a {} -d {}", fish_quote(&names_condition), fish_quote(&name), fish_quote(&description));
+        }
+
+        for subcommand in &self.subcommands {
+            let seen = format!("__fish_seen_subcommand_from {}", subcommand.names.join(" "));
+            let condition = match condition {
+                Some(condition) => format!("{condition}; and {seen}"),
+                None => seen
+            };
+
+            subcommand.args.fish_lines(program, Some(&condition), depth + 1, script);
+        }
+    }
+
+    /// Gives every visible subcommand name and alias, including `help`.
+    fn subcommand_names(&self) -> Vec<String> {
+        self.subcommand_summaries().into_iter().flat_map(|(names, _)| names).collect()
+    }
+
+    /// Gives completions for every name of a visible subcommand, including
+    /// `help`, with its summary.
+    fn subcommand_completions(&self) -> Vec<(String, String)> {
+        self.subcommand_summaries()
+            .into_iter()
+            .flat_map(|(names, summary)| names.into_iter().map(move |name| (name, summary.clone())))
+            .collect()
+    }
+}
+
+
+/// Gives a name that can be used in a shell function name.
+fn bash_name(name: &str) -> String {
+    name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect()
+}
+
+/// Gives the bash statement completing a value, or `None` if there is
+/// nothing to complete.
+fn bash_reply(choices: Option<&Vec<String>>, hint: Option<ValueHint>, cur: &str) -> Option<String> {
+    let words = match (choices, hint) {
+        // `compgen -W` splits and expands its words, so only plain choices
+        // can be given to it.
+        (Some(choices), _) if choices.iter().any(|choice| shell_quote(choice) != *choice) => {
+            let choices: Vec<String> = choices.iter().map(|choice| shell_quote(choice)).collect();
+
+            return Some(format!("COMPREPLY=(); for choice in {}; do [[ $choice == \"{cur}\"* ]] && COMPREPLY+=(\"$choice\"); done",
+                                choices.join(" ")));
+        },
+        (Some(choices), _) => format!("-W \"{}\"", choices.join(" ")),
+        (None, Some(ValueHint::File)) => String::from("-f"),
+        (None, Some(ValueHint::Directory)) => String::from("-d"),
+        (None, None) => return None
+    };
+
+    Some(format!("COMPREPLY=($(compgen {words} -- \"{cur}\"))"))
+}
+
+/// Gives the zsh `_arguments` action completing a value.
+fn zsh_action(choices: Option<&Vec<String>>, hint: Option<ValueHint>) -> String {
+    match (choices, hint) {
+        (Some(choices), _) => format!("({})", choices.iter().map(|choice| zsh_word(choice)).collect::<Vec<String>>().join(" ")),
+        (None, Some(ValueHint::File)) => String::from("_files"),
+        (None, Some(ValueHint::Directory)) => String::from("_files -/"),
+        (None, None) => String::from(" ")
+    }
+}
+
+/// Escapes characters that are special within part of a zsh `_arguments`
+/// spec.
+fn zsh_escape(text: &str, special: &str) -> String {
+    text.chars()
+        .flat_map(|c| if special.contains(c) || c == '\\' { vec!['\\', c] } else { vec![c] })
+        .collect()
+}
+
+/// Escapes a single word of a zsh `_arguments` action, which is split and
+/// expanded as shell words.
+fn zsh_word(text: &str) -> String {
+    text.chars()
+        .flat_map(|c| if c.is_ascii_alphanumeric() || "@%+=,./_-".contains(c) { vec![c] } else { vec!['\\', c] })
+        .collect()
+}
+
+fn zsh_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Gives the fish `complete` switches completing an option's value.
+fn fish_action(choices: Option<&Vec<String>>, hint: Option<ValueHint>) -> String {
+    match (choices, hint) {
+        (Some(choices), _) => format!(" -x -a {}", fish_words(choices)),
+        (None, Some(ValueHint::File)) => String::from(" -r -F"),
+        (None, Some(ValueHint::Directory)) => String::from(" -x -a '(__fish_complete_directories)'"),
+        (None, None) => String::from(" -x")
+    }
+}
+
+/// Quotes words for the `-a` switch, whose argument is split and expanded
+/// as a command-line, so each word is quoted as well if needed.
+fn fish_words(words: &[String]) -> String {
+    let words: Vec<String> = words.iter()
+                                .map(|word| if shell_quote(word) == *word { word.clone() } else { fish_quote(word) })
+                                .collect();
+
+    fish_quote(&words.join(" "))
+}
+
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
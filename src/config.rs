@@ -0,0 +1,155 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::args::{parse_bool, Args};
+
+
+impl Args {
+    /// Reads values for fields, options and switches from a config file.
+    ///
+    /// Each line of the file is `key = value`, where the key is the name of
+    /// a field, or the long name of an option or switch without its leading
+    /// dashes, e.g. `out` for `--out`. A value can be quoted. Lines starting
+    /// with `#` or `;` are comments.
+    ///
+    /// A `[section]` line joins the keys after it to the section's name with
+    /// `-`, e.g. `port` under `[server]` is `server-port`, for
+    /// `--server-port`. A `[]` line ends the section.
+    ///
+    /// Values found on the command-line or in the environment take
+    /// precedence over config files, and a config file read later takes
+    /// precedence over one read earlier, e.g. a user's config over a system
+    /// config. Config files take precedence over defaults.
+    ///
+    /// A file that does not exist is ignored. Unknown keys and invalid
+    /// values are considered errors by `check()`, so every field, option and
+    /// switch should be specified before reading a config file.
+    pub fn config_file(&mut self, path: impl AsRef<Path>) -> &mut Self {
+        let path = path.as_ref();
+
+        match fs::read_to_string(path) {
+            Ok(text) => self.config_str(&text, &path.display().to_string()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => self,
+            Err(e) => {
+                self.error_list.push(format!("cannot read '{}': {e}", path.display()));
+                self
+            }
+        }
+    }
+
+    /// Reads values from config text, as if from a config file at `origin`.
+    ///
+    /// See `config_file()`.
+    pub fn config_str(&mut self, text: &str, origin: &str) -> &mut Self {
+        let mut section = String::new();
+
+        for (number, line) in text.lines().enumerate().map(|(i, line)| (i + 1, line.trim())) {
+            if line.is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                section = match name.trim() {
+                    "" => String::new(),
+                    name => format!("{name}-")
+                };
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (format!("{section}{}", key.trim()), unquote(value.trim())),
+                None => {
+                    self.error_list.push(format!("expected 'key = value' in '{origin}' at line {number}"));
+                    continue;
+                }
+            };
+
+            match self.config_key(&key) {
+                Some(name) if self.switches.iter().any(|(switch, _)| *switch == name) && parse_bool(&value).is_none() => {
+                    self.error_list.push(format!("'{key}' in '{origin}' at line {number} has invalid value '{value}', expected true or false"));
+                },
+                Some(name) if self.choices.get(&name).is_some_and(|choices| !choices.contains(&value)) => {
+                    self.error_list.push(format!("'{key}' in '{origin}' at line {number} has invalid value '{}', expected one of: {}",
+                                                self.redact(&name, &value), self.choices[&name].join(", ")));
+                },
+                Some(name) => self.config.push((name, value, String::from(origin), number)),
+                None => self.error_list.push(format!("unknown key '{key}' in '{origin}' at line {number}"))
+            }
+        }
+
+        self
+    }
+
+    /// Gives the key of the field, option or switch named by a config key,
+    /// or `None` if there is none.
+    fn config_key(&self, key: &str) -> Option<String> {
+        let field = self.field_specs()
+                        .iter()
+                        .find(|field| field.name == key)
+                        .map(|field| String::from(field.name));
+        let option = self.possible_flags
+                        .iter()
+                        .filter_map(|flag| flag.split_once('='))
+                        .find(|(names, _)| names.split('|').any(|name| name.starts_with("--") && name.trim_start_matches('-') == key))
+                        .map(|(names, _)| String::from(names));
+        let switch = self.switches
+                        .iter()
+                        .find(|(switch, _)| switch.starts_with("--") && switch.trim_start_matches('-') == key)
+                        .map(|(switch, _)| switch.clone());
+
+        field.or(option).or(switch)
+    }
+}
+
+
+/// Removes the quotes around a value, if any. Backslash escapes are read
+/// within double quotes.
+fn unquote(value: &str) -> String {
+    if let Some(value) = value.strip_prefix('\'').and_then(|value| value.strip_suffix('\'')) {
+        return String::from(value);
+    }
+
+    match value.strip_prefix('"').and_then(|value| value.strip_suffix('"')) {
+        Some(value) => {
+            let mut unquoted = String::new();
+            let mut chars = value.chars();
+
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => unquoted.extend(chars.next()),
+                    c => unquoted.push(c)
+                }
+            }
+
+            unquoted
+        },
+        None => String::from(value)
+    }
+}
@@ -0,0 +1,209 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::collections::BTreeSet;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::args::Args;
+
+
+impl Args {
+    /// Allows subcommands that are separate executables found on `PATH`, as
+    /// with git and cargo, e.g. `tool foo` runs `tool-foo` when `foo` is not
+    /// a specified subcommand. See `get_external_subcommand()`.
+    ///
+    /// Everything after the first value on the command-line is left for the
+    /// external subcommand, and is not parsed. `check()` considers it an
+    /// error if no executable is found for it. Discovered external
+    /// subcommands are listed by `help()`; the search path is only read
+    /// once for them.
+    ///
+    /// On Windows, an executable is a file with an extension in `PATHEXT`,
+    /// e.g. `tool-foo.exe`.
+    ///
+    /// Panics if any field has already been specified.
+    pub fn allow_external_subcommands(&mut self) -> &mut Self {
+        self.allow_external_subcommands_in(env::var_os("PATH").unwrap_or_default())
+    }
+
+    /// Allows external subcommands, as `allow_external_subcommands()`, but
+    /// searches the directories in `path` instead of `PATH`. `path` is in
+    /// the same form as `PATH`.
+    ///
+    /// Panics if any field has already been specified.
+    pub fn allow_external_subcommands_in(&mut self, path: impl AsRef<OsStr>) -> &mut Self {
+        if !self.arg_names.is_empty() {
+            panic!("external subcommands allowed after arguments");
+        }
+
+        self.external = Some(path.as_ref().to_os_string());
+        self.external_files = OnceLock::new();
+
+        if self.subcommand_at.is_none() {
+            self.subcommand_at = self.tokens.iter().position(|token| !self.is_flag(token));
+            self.tokenize();
+        }
+
+        self
+    }
+
+    /// Gives the path of the external subcommand given on the command-line,
+    /// and every argument after it, or `None` if there is none.
+    ///
+    /// The executable for `foo` is named after the program, e.g. `tool-foo`
+    /// for `tool`, or `tool-remote-foo` for the `remote` subcommand of
+    /// `tool`. The first one found on the search path is given.
+    pub fn get_external_subcommand(&self) -> Option<(PathBuf, Vec<String>)> {
+        let at = self.subcommand_at?;
+        let name = &self.tokens[at];
+
+        if name == "help" || name.contains(['/', '\\']) || self.find_subcommand(name).is_some() {
+            return None;
+        }
+
+        let file_name = format!("{}-{name}", self.external_prefix()?);
+        let file_names: Vec<String> = std::iter::once(file_name.clone())
+                                        .chain(executable_extensions().iter().map(|extension| format!("{file_name}{extension}")))
+                                        .collect();
+
+        env::split_paths(self.external.as_ref()?)
+            .find_map(|dir| file_names.iter().map(|file_name| dir.join(file_name)).find(|path| is_executable(path)))
+            .map(|path| (path, self.tokens[at + 1..].to_vec()))
+    }
+
+    /// Gives the name of every external subcommand found on the search path,
+    /// in order, except those hidden by a specified subcommand and those of
+    /// a specified subcommand, e.g. `remote-add` for `remote`.
+    pub(crate) fn external_subcommands(&self) -> Vec<String> {
+        let (Some(path), Some(prefix)) = (&self.external, self.external_prefix()) else {
+            return Vec::new();
+        };
+
+        let prefix = format!("{prefix}-");
+
+        self.external_files
+            .get_or_init(|| executables_in(path))
+            .iter()
+            .filter_map(|file_name| file_name.strip_prefix(&prefix))
+            .filter(|name| !name.is_empty() && *name != "help")
+            .filter(|name| self.find_subcommand(name.split('-').next().unwrap_or_default()).is_none())
+            .map(String::from)
+            .collect()
+    }
+
+    /// Gives the start of the names of external subcommands, which is the
+    /// program name with any subcommand names joined by `-`, or `None` if
+    /// there is no program name.
+    ///
+    /// An executable extension is left out, e.g. `tool` for `tool.exe`.
+    fn external_prefix(&self) -> Option<String> {
+        let mut prefix = None;
+        self.use_program_name(|program| {
+            let (program, subcommands) = program.split_once(' ').unwrap_or((program, ""));
+
+            prefix = Some(format!("{} {subcommands}", without_extension(program)).trim_end().replace(' ', "-"));
+        });
+
+        prefix.filter(|prefix| !prefix.is_empty())
+    }
+}
+
+
+/// Gives the name of every file on a search path that can be run, in order
+/// and without its executable extension, if any.
+fn executables_in(path: &OsStr) -> Vec<String> {
+    let mut names = BTreeSet::new();
+
+    for dir in env::split_paths(path) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+
+        for path in entries.flatten().map(|entry| entry.path()).filter(|path| is_executable(path)) {
+            if let Some(file_name) = path.file_name().and_then(OsStr::to_str) {
+                names.insert(String::from(without_extension(file_name)));
+            }
+        }
+    }
+
+    names.into_iter().collect()
+}
+
+/// Gives a file name without its executable extension, if it has one, e.g.
+/// `tool` for `tool.exe`.
+pub(crate) fn without_extension(file_name: &str) -> &str {
+    let stem = Path::new(file_name).file_stem().and_then(OsStr::to_str).unwrap_or(file_name);
+
+    match Path::new(file_name).extension().and_then(OsStr::to_str) {
+        Some(extension) if is_executable_extension(extension) => stem,
+        _ => file_name
+    }
+}
+
+/// Indicates whether a file extension, without the `.`, is one of
+/// `executable_extensions()`.
+fn is_executable_extension(extension: &str) -> bool {
+    executable_extensions().iter().any(|known| known[1..].eq_ignore_ascii_case(extension))
+}
+
+
+/// Indicates whether a path is a file that can be run.
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::metadata(path).is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+/// Indicates whether a path is a file that can be run, which is one with an
+/// extension in `PATHEXT`.
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file() && path.extension().and_then(OsStr::to_str).is_some_and(is_executable_extension)
+}
+
+/// Gives the extensions that mark a file as one that can be run, e.g.
+/// `.exe`, which is none where permissions do instead.
+#[cfg(unix)]
+fn executable_extensions() -> Vec<String> {
+    Vec::new()
+}
+
+/// Gives the extensions that mark a file as one that can be run, e.g.
+/// `.exe`, from `PATHEXT`.
+#[cfg(not(unix))]
+fn executable_extensions() -> Vec<String> {
+    env::var("PATHEXT")
+        .unwrap_or_else(|_| String::from(".COM;.EXE;.BAT;.CMD"))
+        .split(';')
+        .filter(|extension| extension.starts_with('.') && extension.len() > 1)
+        .map(str::to_ascii_lowercase)
+        .collect()
+}
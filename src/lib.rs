@@ -25,4 +25,23 @@
 ---------------------------------------------------------------------------- */
 
 mod args;
+mod completion;
+mod config;
+mod external;
+mod macros;
+mod man;
+mod markdown;
+mod matches;
+mod prompt;
+mod provenance;
+mod secret;
+mod shell;
+mod subcommand;
+mod suggest;
 mod tests;
+mod usage;
+
+pub use args::{Args, ArgsError, GroupKind, TokenRules, ValueHint};
+pub use completion::Shell;
+pub use matches::Matches;
+pub use provenance::Source;
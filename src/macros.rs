@@ -0,0 +1,119 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+/// Declares an entire command-line inline.
+///
+/// Expands to the `Args` specifier calls, followed by `check()`. Gives a
+/// `Result` holding a struct with a field for each specifier, so that
+/// misspelt names are caught at compile-time:
+/// - `required name` gives a `String` field.
+/// - `optional name` gives an `Option<String>` field.
+/// - `variadic name` gives a `Vec<String>` field.
+/// - `flag -d`, `flag +d` or `flag --name` give a `bool` field, named after
+///   the flag without its qualifier.
+///
+/// Any specifier can be followed by a description.
+///
+/// ```
+/// # use args_helper::args;
+/// match args!{ required name, optional filter, flag -d "debug" } {
+///     Ok(parsed) => println!("{} {:?} {}", parsed.name, parsed.filter, parsed.d),
+///     Err(error) => eprintln!("{:?}", error.get_problems())
+/// }
+/// ```
+///
+/// An existing `Args` can be used by naming it first, e.g. to print the
+/// usage if `Err()` is given:
+///
+/// ```
+/// # use args_helper::{args, Args};
+/// let mut args = Args::from(vec!["abc", "-d"]);
+/// let parsed = args!(args => required name, flag -d "debug").unwrap();
+///
+/// assert_eq!(parsed.name, "abc");
+/// assert!(parsed.d);
+/// assert_eq!(format!("{args}").trim_end(), "<name> [-d]");
+/// ```
+#[macro_export]
+macro_rules! args {
+    (@munch $a:ident [$($b:tt)*] [$($f:tt)*] [$($i:tt)*] required $name:ident $($desc:literal)? $(, $($rest:tt)*)?) => {
+        $crate::args!(@munch $a
+            [$($b)* $a.required(stringify!($name)); $($a.describe(stringify!($name), $desc);)?]
+            [$($f)* $name: String,]
+            [$($i)* $name: $a.get_unwrap(stringify!($name)),]
+            $($($rest)*)?)
+    };
+    (@munch $a:ident [$($b:tt)*] [$($f:tt)*] [$($i:tt)*] optional $name:ident $($desc:literal)? $(, $($rest:tt)*)?) => {
+        $crate::args!(@munch $a
+            [$($b)* $a.optional(stringify!($name)); $($a.describe(stringify!($name), $desc);)?]
+            [$($f)* $name: Option<String>,]
+            [$($i)* $name: $a.get(stringify!($name)),]
+            $($($rest)*)?)
+    };
+    (@munch $a:ident [$($b:tt)*] [$($f:tt)*] [$($i:tt)*] variadic $name:ident $($desc:literal)? $(, $($rest:tt)*)?) => {
+        $crate::args!(@munch $a
+            [$($b)* $a.variadic(stringify!($name)); $($a.describe(stringify!($name), $desc);)?]
+            [$($f)* $name: Vec<String>,]
+            [$($i)* $name: $a.get_all(stringify!($name)),]
+            $($($rest)*)?)
+    };
+    (@munch $a:ident $b:tt $f:tt $i:tt flag - - $name:ident $($desc:literal)? $(, $($rest:tt)*)?) => {
+        $crate::args!(@flag $a $b $f $i "--" $name [$($desc)?] $($($rest)*)?)
+    };
+    (@munch $a:ident $b:tt $f:tt $i:tt flag - $name:ident $($desc:literal)? $(, $($rest:tt)*)?) => {
+        $crate::args!(@flag $a $b $f $i "-" $name [$($desc)?] $($($rest)*)?)
+    };
+    (@munch $a:ident $b:tt $f:tt $i:tt flag + $name:ident $($desc:literal)? $(, $($rest:tt)*)?) => {
+        $crate::args!(@flag $a $b $f $i "+" $name [$($desc)?] $($($rest)*)?)
+    };
+    (@munch $a:ident [$($b:tt)*] [$($f:tt)*] [$($i:tt)*]) => {{
+        $($b)*
+
+        #[allow(dead_code)]
+        struct Parsed {
+            $($f)*
+        }
+
+        match $a.check() {
+            Ok(_) => Ok(Parsed { $($i)* }),
+            Err(e) => Err(e)
+        }
+    }};
+    (@flag $a:ident [$($b:tt)*] [$($f:tt)*] [$($i:tt)*] $prefix:literal $name:ident [$($desc:literal)?] $($rest:tt)*) => {
+        $crate::args!(@munch $a
+            [$($b)* $a.flag(concat!($prefix, stringify!($name))); $($a.describe(concat!($prefix, stringify!($name)), $desc);)?]
+            [$($f)* $name: bool,]
+            [$($i)* $name: $a.has_flag(concat!($prefix, stringify!($name))),]
+            $($rest)*)
+    };
+    ($a:ident => $($spec:tt)*) => {
+        $crate::args!(@munch $a [] [] [] $($spec)*)
+    };
+    ($($spec:tt)*) => {{
+        let mut args = $crate::Args::new();
+        $crate::args!(@munch args [] [] [] $($spec)*)
+    }};
+}
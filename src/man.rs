@@ -0,0 +1,174 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::fmt::Write;
+
+use crate::args::Args;
+
+
+impl Args {
+    /// Gives a man page for the program, in `man(7)` roff format.
+    ///
+    /// The page has NAME, SYNOPSIS, DESCRIPTION, COMMANDS, OPTIONS,
+    /// ENVIRONMENT and EXIT STATUS sections. The content is taken from `about()`,
+    /// `describe()`, `choices()`, `environment()` and `exit_status()`. The
+    /// SYNOPSIS is the same as the one given by `Display`.
+    ///
+    /// Panics if there is no program name.
+    pub fn man_page(&self) -> String {
+        let program = self.expect_program_name("man page");
+        let about = self.about.as_deref().unwrap_or_default();
+        let mut page = String::new();
+
+        let _ = writeln!(page, ".TH \"{}\" \"1\"", roff_escape(&program.to_uppercase()));
+
+        let _ = writeln!(page, ".SH NAME");
+        match about.lines().next() {
+            Some(summary) => {let _ = writeln!(page, "{} \\- {}", roff_escape(&program), roff_escape(summary));},
+            None => {let _ = writeln!(page, "{}", roff_escape(&program));}
+        }
+
+        let _ = writeln!(page, ".SH SYNOPSIS");
+        let _ = writeln!(page, ".B {}", roff_escape(&program));
+        for item in self.synopsis() {
+            let _ = writeln!(page, "{}", roff_escape(&item));
+        }
+
+        if !about.is_empty() {
+            let _ = writeln!(page, ".SH DESCRIPTION");
+            for paragraph in about.split("\n\n") {
+                let _ = writeln!(page, ".PP");
+                let _ = writeln!(page, "{}", roff_text(paragraph));
+            }
+        }
+
+        let commands = self.subcommand_summaries();
+        if !commands.is_empty() {
+            let _ = writeln!(page, ".SH COMMANDS");
+        }
+
+        for (names, summary) in commands {
+            let names: Vec<String> = names.iter().map(|name| format!("\\fB{}\\fR", roff_escape(name))).collect();
+
+            let _ = writeln!(page, ".TP");
+            let _ = writeln!(page, "{}", names.join(", "));
+            write_item(&mut page, Some(&summary).filter(|summary| !summary.is_empty()), None);
+        }
+
+        let fields = self.field_specs();
+        let flags = self.flag_specs();
+
+        if !fields.is_empty() || !flags.is_empty() {
+            let _ = writeln!(page, ".SH OPTIONS");
+        }
+
+        for field in fields {
+            let _ = writeln!(page, ".TP");
+            let _ = writeln!(page, "\\fI{}\\fR", roff_escape(field.name));
+            write_item(&mut page, field.description, field.choices);
+        }
+
+        for flag in flags {
+            let names: Vec<String> = flag.names
+                                        .iter()
+                                        .map(|name| match flag.value_name {
+                                            Some(value_name) => format!("\\fB{}\\fR=\\fI{}\\fR", roff_escape(name), roff_escape(value_name)),
+                                            None => format!("\\fB{}\\fR", roff_escape(name))
+                                        })
+                                        .collect();
+
+            let _ = writeln!(page, ".TP");
+            let _ = writeln!(page, "{}", names.join(", "));
+            write_item(&mut page, flag.description, flag.choices);
+        }
+
+        if !self.environment.is_empty() {
+            let _ = writeln!(page, ".SH ENVIRONMENT");
+
+            for (variable, description) in &self.environment {
+                let _ = writeln!(page, ".TP");
+                let _ = writeln!(page, "\\fB{}\\fR", roff_escape(variable));
+                let _ = writeln!(page, "{}", roff_text(description));
+            }
+        }
+
+        let _ = writeln!(page, ".SH EXIT STATUS");
+        if self.exit_statuses.is_empty() {
+            let _ = writeln!(page, ".TP");
+            let _ = writeln!(page, "\\fB0\\fR");
+            let _ = writeln!(page, "Success.");
+            let _ = writeln!(page, ".TP");
+            let _ = writeln!(page, "\\fB>0\\fR");
+            let _ = writeln!(page, "An error occurred.");
+        }
+
+        for (status, description) in &self.exit_statuses {
+            let _ = writeln!(page, ".TP");
+            let _ = writeln!(page, "\\fB{}\\fR", roff_escape(&status.to_string()));
+            let _ = writeln!(page, "{}", roff_text(description));
+        }
+
+        page
+    }
+}
+
+
+/// Writes the body of an OPTIONS item.
+fn write_item(page: &mut String, description: Option<&String>, choices: Option<&Vec<String>>) {
+    if let Some(description) = description {
+        let _ = writeln!(page, "{}", roff_text(description));
+    }
+
+    if let Some(choices) = choices {
+        let _ = writeln!(page, "One of: {}.", roff_escape(&choices.join(", ")));
+    }
+
+    if description.is_none() && choices.is_none() {
+        let _ = writeln!(page, "\\&");
+    }
+}
+
+/// Escapes text for roff, so that it is never read as a request.
+fn roff_text(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(roff_escape)
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Escapes a single line of text for roff.
+fn roff_escape(line: &str) -> String {
+    let escaped = line.replace('\\', "\\e").replace('-', "\\-");
+
+    if escaped.starts_with(['.', '\'']) {
+        format!("\\&{escaped}")
+    }
+    else {
+        escaped
+    }
+}
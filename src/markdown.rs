@@ -0,0 +1,177 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use crate::args::{Args, ArgsError, ArgsResult};
+
+
+impl Args {
+    /// Gives a Markdown reference page for the program.
+    ///
+    /// The page has a usage line, the same as the one given by `Display`,
+    /// followed by sections for subcommands, arguments, options, environment
+    /// variables and exit statuses that have been specified.
+    ///
+    /// Panics if there is no program name.
+    pub fn markdown(&self) -> String {
+        let program = self.expect_program_name("markdown");
+        let mut page = String::new();
+
+        let _ = writeln!(page, "# {program}");
+
+        if let Some(about) = &self.about {
+            let _ = writeln!(page);
+            let _ = writeln!(page, "{about}");
+        }
+
+        let _ = writeln!(page);
+        let _ = writeln!(page, "## Usage");
+        let _ = writeln!(page);
+        let _ = writeln!(page, "```");
+        let _ = writeln!(page, "{}", format!("{}", self).trim_end());
+        let _ = writeln!(page, "```");
+
+        let commands = self.subcommand_summaries();
+        if !commands.is_empty() {
+            let _ = writeln!(page);
+            let _ = writeln!(page, "## Commands");
+            let _ = writeln!(page);
+        }
+
+        for (names, summary) in commands {
+            write_item(&mut page, &names, Some(&summary).filter(|summary| !summary.is_empty()), None);
+        }
+
+        let fields = self.field_specs();
+        if !fields.is_empty() {
+            let _ = writeln!(page);
+            let _ = writeln!(page, "## Arguments");
+            let _ = writeln!(page);
+        }
+
+        for field in fields {
+            let example = match (field.is_variadic, field.is_optional) {
+                (true, _) => format!("[{}...]", field.name),
+                (false, true) => format!("[{}]", field.name),
+                (false, false) => format!("<{}>", field.name)
+            };
+
+            write_item(&mut page, &[example], field.description, field.choices);
+        }
+
+        let flags = self.flag_specs();
+        if !flags.is_empty() {
+            let _ = writeln!(page);
+            let _ = writeln!(page, "## Options");
+            let _ = writeln!(page);
+        }
+
+        for flag in flags {
+            let names: Vec<String> = flag.names
+                                        .iter()
+                                        .map(|name| match flag.value_name {
+                                            Some(value_name) => format!("{name}={value_name}"),
+                                            None => name.clone()
+                                        })
+                                        .collect();
+
+            write_item(&mut page, &names, flag.description, flag.choices);
+        }
+
+        if !self.environment.is_empty() {
+            let _ = writeln!(page);
+            let _ = writeln!(page, "## Environment");
+            let _ = writeln!(page);
+        }
+
+        for (variable, description) in &self.environment {
+            write_item(&mut page, std::slice::from_ref(variable), Some(description), None);
+        }
+
+        if !self.exit_statuses.is_empty() {
+            let _ = writeln!(page);
+            let _ = writeln!(page, "## Exit status");
+            let _ = writeln!(page);
+        }
+
+        for (status, description) in &self.exit_statuses {
+            write_item(&mut page, &[status.to_string()], Some(description), None);
+        }
+
+        page
+    }
+
+    /// Returns an error if a committed Markdown file does not match the one
+    /// given by `markdown()`. Intended to be called from a test, so that
+    /// documentation cannot drift from the code.
+    ///
+    /// If the `ARGS_HELPER_UPDATE_DOCS` environment variable is set, the file
+    /// is written instead.
+    pub fn check_markdown(&self, path: impl AsRef<Path>) -> ArgsResult<()> {
+        let path = path.as_ref();
+        let expected = self.markdown();
+
+        if env::var_os("ARGS_HELPER_UPDATE_DOCS").is_some() {
+            return fs::write(path, expected)
+                    .map_err(|e| ArgsError::from(format!("cannot write '{}': {e}", path.display())));
+        }
+
+        let actual = fs::read_to_string(path)
+                        .map_err(|e| ArgsError::from(format!("cannot read '{}': {e}", path.display())))?;
+
+        match actual.lines().zip(expected.lines()).position(|(a, e)| a != e) {
+            Some(line) => Err(ArgsError::from(format!("'{}' does not match the spec at line {}: expected '{}'",
+                                                      path.display(), line + 1, expected.lines().nth(line).unwrap_or_default()))),
+            None if actual.lines().count() != expected.lines().count() => {
+                Err(ArgsError::from(format!("'{}' does not match the spec: expected {} lines, found {}",
+                                            path.display(), expected.lines().count(), actual.lines().count())))
+            },
+            None => Ok(())
+        }
+    }
+}
+
+
+/// Writes a single list item, with its names as code.
+fn write_item(page: &mut String, names: &[String], description: Option<&String>, choices: Option<&Vec<String>>) {
+    let names: Vec<String> = names.iter().map(|name| format!("`{name}`")).collect();
+    let _ = write!(page, "- {}", names.join(", "));
+
+    if let Some(description) = description {
+        let _ = write!(page, " - {}", description.split_whitespace().collect::<Vec<&str>>().join(" "));
+    }
+
+    if let Some(choices) = choices {
+        let choices: Vec<String> = choices.iter().map(|choice| format!("`{choice}`")).collect();
+        let _ = write!(page, " (one of: {})", choices.join(", "));
+    }
+
+    let _ = writeln!(page);
+}
@@ -0,0 +1,252 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::ops::Range;
+
+use crate::args::{field_name, switch_names, Args, ArgsError, ArgsResult, Given};
+
+
+/// The result of matching a command-line against the specification of an
+/// `Args`, borrowing every value from the command-line rather than copying
+/// it.
+///
+/// Values are taken from the command-line and defaults only, not from the
+/// environment or config files. Files named on the command-line for
+/// sensitive options are only read by `get_secret()`.
+pub struct Matches<'a> {
+    spec: &'a Args,
+    values: Vec<&'a str>,
+    flags: Vec<&'a str>,
+    /// The values taken by each field that was found.
+    fields: HashMap<&'a str, Range<usize>>,
+    /// Where in `flags` each flag was found, by its key. A flag that was not
+    /// specified is its own key.
+    found: HashMap<&'a str, Vec<usize>>
+}
+
+
+//---------------------------------------------------------------------------//
+
+
+impl Args {
+    /// Matches a command-line against the fields, flags and options
+    /// specified so far, without copying any of it.
+    ///
+    /// This is intended for matching many command-lines against the same
+    /// specification, which only needs to be built once. The command-line
+    /// given to `new()` or `from()` is not used.
+    ///
+    /// Only this level of the specification is matched. Subcommands are not:
+    /// a subcommand's name and everything after it are matched as fields,
+    /// flags and options of this level, and global flags are not passed on.
+    pub fn matches<'a>(&'a self, command_line: &[&'a str]) -> Matches<'a> {
+        let (flags, values): (Vec<&str>, Vec<&str>) = command_line.iter().partition(|arg| self.is_flag(arg));
+        let fields = self.arg_names
+                        .iter()
+                        .zip(self.field_ranges(values.len()))
+                        .filter_map(|(example, range)| range.map(|range| (field_name(example), range)))
+                        .collect();
+        let mut found: HashMap<&str, Vec<usize>> = HashMap::new();
+
+        for (i, flag) in flags.iter().enumerate() {
+            let name = flag.split_once('=').map_or(*flag, |(name, _)| name);
+            let key = self.flag_keys
+                        .get(name)
+                        .map(String::as_str)
+                        .or_else(|| self.abbreviated_key(name));
+
+            found.entry(key.unwrap_or(name)).or_default().push(i);
+        }
+
+        Matches {
+            spec: self,
+            values,
+            flags,
+            fields,
+            found
+        }
+    }
+}
+
+
+impl Debug for Matches<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let flags: Vec<String> = self.flags.iter().map(|flag| self.spec.redact_flag(flag)).collect();
+
+        f.debug_struct("Matches")
+            .field("values", &self.spec.redact_values(&self.values))
+            .field("flags", &flags)
+            .finish()
+    }
+}
+
+
+impl Given for Matches<'_> {
+    fn flags(&self) -> Vec<&str> {
+        self.flags.clone()
+    }
+
+    fn given_flags(&self, key: &str) -> Vec<&str> {
+        self.found(key).iter().map(|&i| self.flags[i]).collect()
+    }
+
+    fn field_values(&self, name: &str) -> Vec<String> {
+        self.get_all(name).iter().map(|value| String::from(*value)).collect()
+    }
+
+    fn value(&self, name: &str) -> Option<String> {
+        self.get(name).map(String::from).or_else(|| self.get_secret(name))
+    }
+
+    fn is_given(&self, name: &str) -> bool {
+        self.fields.contains_key(name) || !self.found(name).is_empty()
+    }
+}
+
+
+impl<'a> Matches<'a> {
+    /// Returns an error if any required fields are not found, or if the
+    /// command-line breaks any other rule that `Args::check()` applies to
+    /// the command-line itself.
+    pub fn check(&self) -> ArgsResult<&Self> {
+        let mut problems: Vec<(String, Option<String>)> = self.spec
+                                                            .field_specs()
+                                                            .iter()
+                                                            .filter(|field| !field.is_optional && !self.fields.contains_key(field.name))
+                                                            .filter(|field| !self.spec.is_excused(field.name, self))
+                                                            .map(|field| (format!("required argument '{}' not found", field.name), None))
+                                                            .collect();
+        problems.extend(self.spec.secret_problems(self).into_iter().map(|problem| (problem, None)));
+        problems.extend(self.spec.choice_problems(self));
+        problems.extend(self.spec.unknown_flag_problems(self));
+        problems.extend(self.spec.abbreviation_problems(self).into_iter().map(|problem| (problem, None)));
+        problems.extend(self.spec.group_problems(self).into_iter().map(|problem| (problem, None)));
+        problems.extend(self.spec.condition_problems(self).into_iter().map(|problem| (problem, None)));
+
+        if problems.is_empty() {
+            Ok(self)
+        }
+        else {
+            Err(ArgsError::with_suggestions(problems))
+        }
+    }
+
+    /// Gives the value of a named field, or `None` if it was not found.
+    ///
+    /// For a variadic field, gives the first value. For an optional or
+    /// variadic field that was not found, gives its default, if any.
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        self.get_all(name)
+            .first()
+            .copied()
+            .or_else(|| self.spec.get_default(name))
+    }
+
+    /// Gives every value of a named field, which is empty if it was not
+    /// found.
+    ///
+    /// Mostly useful for variadic fields.
+    pub fn get_all(&self, name: &str) -> &[&'a str] {
+        self.fields
+            .get(name)
+            .map_or(&[], |range| &self.values[range.clone()])
+    }
+
+    /// Indicates whether a specific flag was found on the command-line.
+    ///
+    /// Any of the flag's names can be given.
+    pub fn has_flag(&self, name: &str) -> bool {
+        !self.found(name).is_empty()
+    }
+
+    /// Gives the value of a named option, or `None` if it was not found.
+    ///
+    /// If the option is given more than once, the last value is used. If it
+    /// is not given at all, its default is used, if any. A file named for a
+    /// sensitive option is not read, see `get_secret()`.
+    pub fn get_option(&self, name: &str) -> Option<&'a str> {
+        self.given_option(name).or_else(|| self.spec.get_default(self.key(name)))
+    }
+
+    /// Gives the value of a named option, as `get_option()`, except that a
+    /// sensitive option that was not given is read from the file named by
+    /// its file option, if any. See `Args::sensitive_file()`.
+    ///
+    /// The file is read by the first call for it, not by `matches()`.
+    pub fn get_secret(&self, name: &str) -> Option<String> {
+        let key = self.key(name);
+
+        self.given_option(name)
+            .map(String::from)
+            .or_else(|| self.spec
+                            .secret_files
+                            .iter()
+                            .find(|(secret, _)| secret == key)
+                            .and_then(|(_, file_key)| self.get_option(file_key))
+                            .and_then(|path| self.spec.read_secret(path).ok()))
+            .or_else(|| self.spec.get_default(key).map(String::from))
+    }
+
+    /// Gives the state of a switch, which is the last of its on or off names
+    /// found on the command-line, or its default if neither was found.
+    pub fn get_bool(&self, name: &str) -> Option<bool> {
+        let (on, off) = switch_names(name);
+        let last = |name: &str| self.found(name).last().copied();
+
+        match (last(&on), last(&off)) {
+            (Some(on), Some(off)) => Some(on > off),
+            (Some(_), None) => Some(true),
+            (None, Some(_)) => Some(false),
+            (None, None) => self.spec.switches.iter().find(|(name, _)| *name == on).and_then(|(_, default)| *default)
+        }
+    }
+
+    /// Gives the last value of a named option found on the command-line.
+    fn given_option(&self, name: &str) -> Option<&'a str> {
+        self.found(name)
+            .iter()
+            .rev()
+            .find_map(|&i| self.flags[i].split_once('='))
+            .map(|(_, value)| value)
+    }
+
+    /// Gives the key of a flag's names, e.g. `-o|--out` for `-o`, or the
+    /// name itself if it has not been specified.
+    fn key<'k>(&self, name: &'k str) -> &'k str
+    where
+        'a: 'k
+    {
+        self.spec.flag_keys.get(name).map_or(name, String::as_str)
+    }
+
+    /// Gives where in the command-line's flags a flag was found, by any of
+    /// its names.
+    fn found(&self, name: &str) -> &[usize] {
+        self.found.get(self.key(name)).map_or(&[], Vec::as_slice)
+    }
+}
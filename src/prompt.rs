@@ -0,0 +1,128 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use crate::args::Args;
+
+
+impl Args {
+    /// Prompts for every missing required field, if stdin is a terminal.
+    ///
+    /// Prompts are written to stderr. Sensitive fields are read without
+    /// echoing, which needs `stty`; where echo cannot be turned off, e.g. on
+    /// Windows, sensitive fields are not prompted for and are left missing.
+    /// If stdin is not a terminal, nothing is done, so `check()` reports the
+    /// missing fields as usual.
+    pub fn interactive(&mut self) -> &mut Self {
+        if io::stdin().is_terminal() {
+            let _ = self.prompt_with(&mut io::stdin().lock(), &mut io::stderr(), set_echo);
+        }
+
+        self
+    }
+
+    /// Prompts for every missing required field, reading answers from
+    /// `reader` and writing prompts to `writer`.
+    ///
+    /// Each prompt shows the field's description, choices and default. An
+    /// empty answer takes the default, if there is one, and an answer that
+    /// is not one of the choices is asked for again. A field is left missing
+    /// if `reader` runs out.
+    pub fn prompt_missing(&mut self, reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<&mut Self> {
+        self.prompt_with(reader, writer, |_| true)
+    }
+
+    /// Prompts for every missing required field, turning echo off and on
+    /// again with `echo` for sensitive fields, which returns whether it
+    /// could.
+    pub(crate) fn prompt_with(&mut self, reader: &mut impl BufRead, writer: &mut impl Write, mut echo: impl FnMut(bool) -> bool) -> io::Result<&mut Self> {
+        for name in self.missing_fields() {
+            let choices = self.choices.get(&name).cloned().unwrap_or_default();
+            let default = self.defaults.get(&name).cloned();
+            let is_sensitive = self.sensitive.contains(&name);
+
+            let mut prompt = self.descriptions.get(&name).cloned().unwrap_or_else(|| name.clone());
+            if !choices.is_empty() {
+                prompt.push_str(&format!(" ({})", choices.join("/")));
+            }
+            if let Some(default) = &default {
+                prompt.push_str(&format!(" [{}]", self.redact(&name, default)));
+            }
+
+            loop {
+                // A secret that would be shown is left missing instead.
+                if is_sensitive && !echo(false) {
+                    break;
+                }
+
+                write!(writer, "{prompt}: ")?;
+                writer.flush()?;
+
+                let mut answer = String::new();
+                let read = reader.read_line(&mut answer);
+                if is_sensitive {
+                    echo(true);
+                    writeln!(writer)?;
+                }
+
+                if read? == 0 {
+                    return Ok(self);
+                }
+
+                let answer = match answer.trim_end_matches(['\r', '\n']) {
+                    "" => match &default {
+                        Some(default) => default.clone(),
+                        None => continue
+                    },
+                    answer => String::from(answer)
+                };
+
+                if !choices.is_empty() && !choices.contains(&answer) {
+                    writeln!(writer, "expected one of: {}", choices.join(", "))?;
+                    continue;
+                }
+
+                self.supply(&name, answer);
+                break;
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+
+/// Turns terminal echo on or off, where `stty` is available, giving whether
+/// it could.
+fn set_echo(on: bool) -> bool {
+    Command::new("stty")
+        .arg(if on { "echo" } else { "-echo" })
+        .stdin(Stdio::inherit())
+        .status()
+        .is_ok_and(|status| status.success())
+}
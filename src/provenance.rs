@@ -0,0 +1,163 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::fmt::{self, Display, Write};
+
+use crate::args::{switch_names, Args};
+
+
+/// Where the value of a field, flag, option or switch came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    /// The command-line, at an index of `argv`, where the program name is
+    /// at index 0.
+    CommandLine(usize),
+    /// An environment variable.
+    Environment(String),
+    /// A config file, at a line number.
+    Config(String, usize),
+    /// A file named on the command-line for a sensitive option, where `-`
+    /// is stdin. See `Args::sensitive_file()`.
+    File(String),
+    /// A prompt for a missing field.
+    Prompt,
+    /// The specified default.
+    Default
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::CommandLine(index) => write!(f, "command-line argument {index}"),
+            Source::Environment(variable) => write!(f, "environment variable '{variable}'"),
+            Source::Config(path, line) => write!(f, "'{path}' at line {line}"),
+            Source::File(path) if path == "-" => write!(f, "stdin"),
+            Source::File(path) => write!(f, "file '{path}'"),
+            Source::Prompt => write!(f, "prompt"),
+            Source::Default => write!(f, "default")
+        }
+    }
+}
+
+
+//---------------------------------------------------------------------------//
+
+
+impl Args {
+    /// Gives where the value of a named field, flag, option or switch came
+    /// from, or `None` if it has no value.
+    ///
+    /// The sources are, in order of precedence, the command-line, the
+    /// environment, config files, and defaults. A field can also be given a
+    /// value by a prompt.
+    pub fn source_of(&self, name: &str) -> Option<Source> {
+        if let Some(position) = self.field_specs().iter().position(|field| field.name == name) {
+            let index = self.field_ranges(self.command_line.len())[position]
+                            .as_ref()
+                            .and_then(|range| self.tokens
+                                                .iter()
+                                                .enumerate()
+                                                .filter(|(_, token)| !self.is_flag(token))
+                                                .nth(range.start))
+                            .map(|(index, _)| index);
+
+            return match index {
+                Some(index) if self.has_value(name) => Some(Source::CommandLine(self.argv_offset + index + 1)),
+                _ if self.has_value(name) => Some(Source::Prompt),
+                _ => self.layered_source(name)
+                        .map(|(_, source)| source)
+                        .or_else(|| self.get(name).map(|_| Source::Default))
+            };
+        }
+
+        let (on, off) = switch_names(name);
+        if self.switches.iter().any(|(switch, _)| *switch == on) {
+            return self.last_flag(|flag| flag == on || flag == off)
+                        .or_else(|| self.layered_source(&on).map(|(_, source)| source))
+                        .or_else(|| self.get_bool(&on).map(|_| Source::Default));
+        }
+
+        let aliases = self.flag_aliases(name);
+        let is_alias = |flag: &str| aliases.iter().any(|alias| alias == flag.split_once('=').map_or(flag, |(flag, _)| flag));
+
+        self.last_flag(is_alias)
+            .or_else(|| self.layered_source(&aliases.join("|")).map(|(_, source)| source))
+            .or_else(|| self.defaults.contains_key(&aliases.join("|")).then_some(Source::Default))
+    }
+
+    /// Gives a listing of every specified field, flag, option and switch,
+    /// with its value and where the value came from, e.g. to be shown by an
+    /// `--explain-config` flag. Sensitive values are redacted.
+    pub fn explain(&self) -> String {
+        let mut listing = String::new();
+
+        for field in self.field_specs() {
+            let value = match field.is_variadic {
+                true => Some(self.get_all(field.name).join(" ")).filter(|values| !values.is_empty()),
+                false => self.get(field.name)
+            };
+
+            let value = value.map(|value| String::from(self.redact(field.name, &value)));
+            write_entry(&mut listing, field.name, value, self.source_of(field.name));
+        }
+
+        for flag in self.flag_specs() {
+            let name = flag.names.last().map_or("", String::as_str);
+            let value = match (flag.value_name, self.switches.iter().any(|(switch, _)| *switch == flag.names[0])) {
+                (Some(_), _) => self.get_option(name),
+                (None, true) => self.get_bool(name).map(|on| on.to_string()),
+                (None, false) => self.has_flag(name).then(|| String::from("true"))
+            };
+
+            let value = value.map(|value| String::from(self.redact(name, &value)));
+            write_entry(&mut listing, &flag.names.join("|"), value, self.source_of(name));
+        }
+
+        listing
+    }
+
+    /// Gives the command-line position of the last flag that matches,
+    /// including global flags given at other levels.
+    fn last_flag(&self, matches: impl Fn(&str) -> bool) -> Option<Source> {
+        self.outer_globals
+            .iter()
+            .cloned()
+            .chain(self.own_flags())
+            .chain(self.inner_globals.iter().cloned())
+            .filter(|(_, flag)| matches(flag))
+            .last()
+            .map(|(index, _)| Source::CommandLine(index))
+    }
+}
+
+
+/// Writes a single line of an explanation.
+fn write_entry(listing: &mut String, name: &str, value: Option<String>, source: Option<Source>) {
+    let _ = match (value, source) {
+        (Some(value), Some(source)) => writeln!(listing, "{name} = {value} ({source})"),
+        _ => writeln!(listing, "{name} is not set")
+    };
+}
@@ -0,0 +1,180 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::fs;
+use std::io::{self, Read};
+use std::sync::{OnceLock, PoisonError};
+
+use crate::args::{Args, Given, ValueHint};
+use crate::provenance::Source;
+
+
+/// What a sensitive value is shown as.
+pub(crate) const REDACTED: &str = "<redacted>";
+
+
+impl Args {
+    /// Marks a named option as sensitive, and specifies another option that
+    /// names a file to read its value from, or `-` for stdin, e.g.
+    /// `--token-file=FILE` for `--token`. Secrets then never need to be
+    /// given on the command-line, where other users can see them.
+    ///
+    /// The value is the file's contents, less a final newline. It is used
+    /// when the sensitive option is not given, ahead of the environment and
+    /// config files, and the file is only read when the value is first
+    /// needed. Each file, and stdin, is read at most once. `check()` considers it an error if both
+    /// options are given, or if the file cannot be read.
+    ///
+    /// The file option is global if the sensitive option is, or becomes so.
+    ///
+    /// Panics if no option with the name has been specified, or if the file
+    /// option has already been specified.
+    pub fn sensitive_file(&mut self, name: &str, file_option: &str) -> &mut Self {
+        let key = self.spec_key(name);
+        let value_name = "FILE";
+
+        if !self.possible_flags.iter().any(|flag| flag.split_once('=').is_some_and(|(names, _)| names == key)) {
+            panic!("no option '{name}' specified");
+        }
+
+        self.sensitive(name)
+            .option(file_option, value_name)
+            .describe(file_option, &format!("Read {name} from {value_name}, or - for stdin"))
+            .hint(file_option, ValueHint::File);
+
+        let file_key = self.spec_key(file_option);
+        self.secret_files.push((key.clone(), file_key));
+
+        if self.globals.iter().any(|global| global.split_once('=').is_some_and(|(names, _)| names == key)) {
+            self.global(file_option);
+        }
+
+        self
+    }
+
+    /// Gives the value of a sensitive option, by its key, from the file
+    /// named by its file option, if that was given and can be read.
+    pub(crate) fn secret(&self, key: &str) -> Option<(String, Source)> {
+        let (_, file_key) = self.secret_files.iter().find(|(secret, _)| secret == key)?;
+        let path = self.get_option(file_key.split('|').next()?)?;
+
+        self.read_secret(&path).ok().map(|value| (value, Source::File(path)))
+    }
+
+    /// Gives the contents of a file named for a sensitive option, less a
+    /// final newline, reading each file only the first time.
+    pub(crate) fn read_secret(&self, path: &str) -> Result<String, String> {
+        self.secret_reads
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(String::from(path))
+            .or_insert_with(|| read_secret(path))
+            .clone()
+    }
+
+    /// Gives a problem for every sensitive option given along with its file
+    /// option, and for every file named by a file option that cannot be
+    /// read.
+    ///
+    /// Options are named by their last name, e.g. `--token` for
+    /// `-t|--token`.
+    pub(crate) fn secret_problems(&self, given: &impl Given) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        for (key, file_key) in &self.secret_files {
+            let name = key.rsplit('|').next().unwrap_or_default();
+            let file_option = file_key.rsplit('|').next().unwrap_or_default();
+
+            if !given.given_flags(key).is_empty() {
+                if !given.given_flags(file_key).is_empty() {
+                    problems.push(format!("only one of '{name}' and '{file_option}' can be given"));
+                }
+            }
+            else if let Some(path) = given.value(file_option) {
+                if let Err(e) = self.read_secret(&path) {
+                    problems.push(format!("cannot read '{path}': {e}"));
+                }
+            }
+        }
+
+        problems
+    }
+
+    /// Indicates whether a named field or option, or an option's key, has
+    /// been marked as sensitive.
+    pub(crate) fn is_sensitive(&self, name: &str) -> bool {
+        self.sensitive.iter().any(|key| key == name || key.split('|').any(|n| n == name))
+    }
+
+    /// Gives a value of a named field or option, or `<redacted>` if it is
+    /// sensitive.
+    pub(crate) fn redact<'a>(&self, name: &str, value: &'a str) -> &'a str {
+        match self.is_sensitive(name) {
+            true => REDACTED,
+            false => value
+        }
+    }
+
+    /// Gives a flag found on the command-line, with its value redacted if it
+    /// is a sensitive option, e.g. `--token=<redacted>`.
+    pub(crate) fn redact_flag(&self, flag: &str) -> String {
+        match flag.split_once('=') {
+            Some((name, value)) => format!("{name}={}", self.redact(name, value)),
+            None => String::from(flag)
+        }
+    }
+
+    /// Gives the values found on the command-line, with those taken by
+    /// sensitive fields redacted.
+    pub(crate) fn redact_values(&self, values: &[impl AsRef<str>]) -> Vec<String> {
+        let mut redacted: Vec<String> = values.iter().map(|value| String::from(value.as_ref())).collect();
+
+        for (field, range) in self.field_specs().iter().zip(self.field_ranges(values.len())) {
+            if let Some(range) = range.filter(|_| self.is_sensitive(field.name)) {
+                redacted[range].fill(String::from(REDACTED));
+            }
+        }
+
+        redacted
+    }
+}
+
+
+/// Reads a secret from a file, or from stdin for `-`, less a final newline.
+fn read_secret(path: &str) -> Result<String, String> {
+    static STDIN: OnceLock<Result<String, String>> = OnceLock::new();
+
+    let text = match path {
+        "-" => STDIN.get_or_init(|| {
+                        let mut text = String::new();
+                        io::stdin().read_to_string(&mut text).map(|_| text).map_err(|e| e.to_string())
+                    })
+                    .clone(),
+        path => fs::read_to_string(path).map_err(|e| e.to_string())
+    }?;
+
+    Ok(String::from(text.strip_suffix('\n').map_or(text.as_str(), |text| text.strip_suffix('\r').unwrap_or(text))))
+}
@@ -0,0 +1,189 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use crate::args::{Args, ArgsError, ArgsResult};
+
+
+impl Args {
+    /// Creates a new `Self` from a command-line written as a single string,
+    /// e.g. `build "my target" --out='a b'`, split into words as by a POSIX
+    /// shell. The words are taken as with `From<Vec<String>>`, so the string
+    /// should not include the program name.
+    ///
+    /// - Words are separated by spaces, tabs and newlines.
+    /// - Anything inside single quotes is taken as is.
+    /// - Inside double quotes, a backslash only escapes `$`, `` ` ``, `"`,
+    ///   `\` and a newline.
+    /// - Outside quotes, a backslash escapes any character.
+    /// - A backslash before a newline joins the lines.
+    /// - A word starting with `#` starts a comment, up to the end of the
+    ///   line.
+    ///
+    /// Nothing is expanded, so `$HOME` and `*` are taken literally.
+    ///
+    /// Returns an error if a quote is not closed, or the string ends with a
+    /// backslash.
+    pub fn from_shell_str(text: &str) -> ArgsResult<Self> {
+        Ok(Self::from(shell_words(text)?))
+    }
+
+    /// Gives the command-line as a single string that can be pasted into a
+    /// POSIX shell, e.g. for logs or to show how to run the program again.
+    /// Words are quoted only where needed.
+    ///
+    /// The words are in a canonical order: the program name, if there is
+    /// one, then the values, then the flags and options in the order they
+    /// were specified, then any subcommand with its own command-line in the
+    /// same order. Abbreviated flags are given in full. Without a program
+    /// name, `from_shell_str()` gives back the same values, flags and
+    /// options, except for sensitive values, which are redacted.
+    pub fn to_shell_string(&self) -> String {
+        self.get_program_name()
+            .into_iter()
+            .chain(self.canonical_words())
+            .map(|word| shell_quote(&word))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    /// Gives the words of this level of the command-line and below, in
+    /// canonical order. See `to_shell_string()`.
+    fn canonical_words(&self) -> Vec<String> {
+        let specs = self.flag_specs();
+        let mut flags: Vec<String> = self.own_flags().into_iter().map(|(_, flag)| flag).collect();
+
+        // Unspecified flags go last, in the order they were given.
+        flags.sort_by_key(|flag| {
+            let name = flag.split_once('=').map_or(flag.as_str(), |(name, _)| name);
+
+            specs.iter().position(|spec| spec.names.iter().any(|n| n == name)).unwrap_or(usize::MAX)
+        });
+
+        let mut words = self.redact_values(&self.command_line);
+        words.extend(flags.iter().map(|flag| self.redact_flag(flag)));
+
+        match (self.subcommand_at, self.get_subcommand()) {
+            (_, Some((name, args))) => {
+                if !self.multi_call {
+                    words.push(String::from(name));
+                }
+
+                words.extend(args.canonical_words());
+            },
+            (Some(at), None) => words.extend(self.tokens[at..].iter().cloned()),
+            (None, None) => ()
+        }
+
+        words
+    }
+}
+
+
+/// Splits a string into words, as a POSIX shell does, without expanding
+/// anything. See `Args::from_shell_str()`.
+pub(crate) fn shell_words(text: &str) -> ArgsResult<Vec<String>> {
+    let mut words = Vec::new();
+    let mut chars = text.char_indices().peekable();
+
+    while let Some(&(_, c)) = chars.peek() {
+        match c {
+            ' ' | '\t' | '\n' => {chars.next();},
+            '#' => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+            },
+            _ => words.extend(shell_word(&mut chars)?)
+        }
+    }
+
+    Ok(words)
+}
+
+/// Reads a single word, up to the first unquoted space, tab or newline.
+/// Gives `None` if there was only a line continuation, which is not a word.
+fn shell_word(chars: &mut Peekable<CharIndices>) -> ArgsResult<Option<String>> {
+    let mut word = String::new();
+    let mut is_word = false;
+
+    while let Some((at, c)) = chars.next_if(|(_, c)| !matches!(c, ' ' | '\t' | '\n')) {
+        is_word |= c != '\\' || chars.peek().is_none_or(|(_, c)| *c != '\n');
+
+        match c {
+            '\'' => loop {
+                match chars.next() {
+                    Some((_, '\'')) => break,
+                    Some((_, c)) => word.push(c),
+                    None => return Err(ArgsError::from(format!("unterminated single quote at position {at}")))
+                }
+            },
+            '"' => loop {
+                match chars.next() {
+                    Some((_, '"')) => break,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, '\n')) => (),
+                        Some((_, c)) if matches!(c, '$' | '`' | '"' | '\\') => word.push(c),
+                        Some((_, c)) => {
+                            word.push('\\');
+                            word.push(c);
+                        },
+                        None => return Err(ArgsError::from(format!("unterminated double quote at position {at}")))
+                    },
+                    Some((_, c)) => word.push(c),
+                    None => return Err(ArgsError::from(format!("unterminated double quote at position {at}")))
+                }
+            },
+            '\\' => match chars.next() {
+                Some((_, '\n')) => (),
+                Some((_, c)) => word.push(c),
+                None => return Err(ArgsError::from(format!("unterminated escape at position {at}")))
+            },
+            c => word.push(c)
+        }
+    }
+
+    Ok(Some(word).filter(|_| is_word))
+}
+
+/// Quotes a word for a POSIX shell, if needed, e.g. `'a b'` for `a b`, or
+/// `'it'\''s'` for `it's`.
+///
+/// A word that looks like a variable assignment, e.g. `a=b`, is quoted, so
+/// that it cannot be taken as one before the program name.
+pub(crate) fn shell_quote(word: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "@%+=:,./_-".contains(c);
+    let is_assignment = word.split_once('=').is_some_and(|(name, _)| {
+        name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    });
+
+    if !word.is_empty() && word.chars().all(is_safe) && !is_assignment {
+        String::from(word)
+    }
+    else {
+        format!("'{}'", word.replace('\'', r"'\''"))
+    }
+}
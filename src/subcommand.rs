@@ -0,0 +1,425 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::fmt::Write;
+
+use crate::args::{flag_names, Args};
+use crate::external::without_extension;
+
+
+/// A subcommand, with its own specification.
+pub(crate) struct Subcommand {
+    pub(crate) names: Vec<String>,
+    pub(crate) hidden: bool,
+    pub(crate) args: Args
+}
+
+
+//---------------------------------------------------------------------------//
+
+
+impl Args {
+    /// Specifies a subcommand, e.g. `build` in `tool build --release`,
+    /// whose own fields, flags and options are specified by `spec`.
+    ///
+    /// Several names for the same subcommand can be given, separated by `|`,
+    /// e.g. `build|b`. The first is its name, and the others are aliases.
+    ///
+    /// The subcommand is found as the first value on the command-line, and
+    /// everything after it is given to the subcommand's `Args`. Once a
+    /// subcommand has been specified, `check()` considers it an error if
+    /// no subcommand, or an unknown one, is given. A `help` subcommand is
+    /// always available, see `help_requested()`.
+    ///
+    /// Panics if a subcommand name is repeated or is `help`, or if any field
+    /// has already been specified.
+    pub fn subcommand(&mut self, name: &str, spec: impl FnOnce(&mut Args)) -> &mut Self {
+        let names: Vec<String> = name.split('|').map(String::from).collect();
+
+        if !self.arg_names.is_empty() {
+            panic!("subcommand '{name}' specified after arguments");
+        }
+
+        for name in &names {
+            if name == "help" || self.find_subcommand(name).is_some() {
+                panic!("subcommand '{name}' specified twice");
+            }
+        }
+
+        if self.subcommand_at.is_none() {
+            let first_value = self.tokens.iter().position(|token| !self.is_flag(token));
+
+            if let Some(i) = first_value.filter(|i| names.contains(&self.tokens[*i]) || self.tokens[*i] == "help") {
+                self.subcommand_at = Some(i);
+                self.tokenize();
+            }
+        }
+
+        let found = self.subcommand_at.filter(|i| names.contains(&self.tokens[*i]));
+        let tokens = match found {
+            Some(i) => self.tokens[i + 1..].to_vec(),
+            None => Vec::new()
+        };
+
+        let mut args = Args::from(tokens);
+        self.use_program_name(|program| match self.multi_call && found.is_some() {
+            true => args.set_program_name(Some(String::from(program))),
+            false => args.set_program_name(Some(format!("{program} {}", names[0])))
+        });
+        self.inherit_globals(&mut args, found);
+        spec(&mut args);
+
+        if found.is_some() {
+            self.inner_globals = args.given_globals();
+            self.tokenize();
+        }
+
+        self.subcommands.push(Subcommand {
+            names,
+            hidden: false,
+            args
+        });
+
+        self
+    }
+
+    /// Marks a named flag or option as global, so that it is given to every
+    /// subcommand, at any depth, and can be found on the command-line before
+    /// or after any subcommand, e.g. both `tool --verbose remote add x` and
+    /// `tool remote add x --verbose`.
+    ///
+    /// A global flag or option is found by `has_flag()` and `get_option()`
+    /// at every level, with the last one given winning. Its description,
+    /// default, choices and whether it is sensitive go with it, and so does
+    /// the file option of a sensitive option, see `sensitive_file()`.
+    ///
+    /// Panics if no flag or option with the name has been specified, or if
+    /// any subcommand has already been specified.
+    pub fn global(&mut self, name: &str) -> &mut Self {
+        if !self.subcommands.is_empty() {
+            panic!("global flag '{name}' specified after subcommands");
+        }
+
+        let Some(flag) = self.possible_flags.iter().find(|flag| flag_names(flag).iter().any(|n| n == name)).cloned() else {
+            panic!("no flag or option '{name}' specified")
+        };
+
+        if self.globals.contains(&flag) {
+            return self;
+        }
+
+        let key = String::from(flag.split_once('=').map_or(flag.as_str(), |(names, _)| names));
+        self.globals.push(flag);
+
+        let files: Vec<String> = self.secret_files.iter().filter(|(secret, _)| *secret == key).map(|(_, file_key)| file_key.clone()).collect();
+
+        for file_key in files {
+            self.global(file_key.split('|').next().unwrap_or_default());
+        }
+
+        self
+    }
+
+    /// Selects a subcommand by the name the program was run as, for a
+    /// single program installed under several names, as with busybox, e.g.
+    /// `ls -l` runs the `ls` subcommand of a program whose main name is
+    /// `busybox`.
+    ///
+    /// The name is the file name of the program name, as shown by
+    /// `Display`, without an executable extension, e.g. `ls` for `ls.exe`
+    /// on Windows. When run as `main`, or without a program name, the
+    /// subcommand is the first value on the command-line as usual, e.g.
+    /// `busybox ls -l`. When run as any other name, the whole command-line
+    /// is given to the subcommand with that name, and `check()` considers it
+    /// an error if there is none.
+    ///
+    /// Panics if any field or subcommand has already been specified.
+    pub fn multi_call(&mut self, main: &str) -> &mut Self {
+        if !self.arg_names.is_empty() || !self.subcommands.is_empty() {
+            panic!("multi-call program '{main}' specified after arguments or subcommands");
+        }
+
+        let mut name = None;
+        self.use_program_name(|program| name = Some(String::from(without_extension(program))).filter(|program| !program.is_empty() && program != main));
+
+        if let Some(name) = name {
+            self.tokens.insert(0, name);
+            self.subcommand_at = Some(0);
+            self.multi_call = true;
+            self.tokenize();
+        }
+
+        self
+    }
+
+    /// Hides a named subcommand from help, completions and documentation.
+    /// It can still be given on the command-line.
+    ///
+    /// Panics if no subcommand with the name has been specified.
+    pub fn hide_subcommand(&mut self, name: &str) -> &mut Self {
+        match self.subcommands.iter_mut().find(|subcommand| subcommand.names.iter().any(|n| n == name)) {
+            Some(subcommand) => subcommand.hidden = true,
+            None => panic!("no subcommand '{name}' specified")
+        }
+
+        self
+    }
+
+    /// Gives the name of the subcommand found on the command-line, and its
+    /// `Args`, or `None` if there is none.
+    ///
+    /// The name is the subcommand's first name, even if an alias was given.
+    /// The `help` subcommand is not given here, see `help_requested()`.
+    pub fn get_subcommand(&self) -> Option<(&str, &Args)> {
+        let subcommand = self.find_subcommand(&self.tokens[self.subcommand_at?])?;
+
+        Some((&subcommand.names[0], &subcommand.args))
+    }
+
+    /// Gives the help for the program or a subcommand, if it was asked for
+    /// on the command-line, or `None` if it was not.
+    ///
+    /// Help is asked for by `--help`, or `-h` if it is not a specified flag,
+    /// after the program or a subcommand, e.g. `tool build --help`, or by
+    /// the `help` subcommand, e.g. `tool help build`.
+    pub fn help_requested(&self) -> Option<String> {
+        if self.has_flag("--help") || (self.has_flag("-h") && !self.is_declared_flag("-h")) {
+            return Some(self.help());
+        }
+
+        let at = self.subcommand_at?;
+
+        if self.tokens[at] != "help" {
+            return self.get_subcommand().and_then(|(_, args)| args.help_requested());
+        }
+
+        let mut args = self;
+        for name in self.tokens[at + 1..].iter().filter(|token| !token.starts_with('-')) {
+            match args.find_subcommand(name) {
+                Some(subcommand) => args = &subcommand.args,
+                None => break
+            }
+        }
+
+        Some(args.help())
+    }
+
+    /// Gives the help for the program, which is its example command-line,
+    /// its description, and a listing of its visible and external
+    /// subcommands, fields, flags and options with their descriptions.
+    pub fn help(&self) -> String {
+        let mut sections: Vec<(&str, Vec<(String, String)>)> = Vec::new();
+
+        sections.push(("Commands", self.subcommand_summaries()
+                                        .into_iter()
+                                        .map(|(names, summary)| (names.join(", "), summary))
+                                        .collect()));
+
+        sections.push(("External commands", self.external_subcommands()
+                                                .into_iter()
+                                                .map(|name| (name, String::new()))
+                                                .collect()));
+
+        sections.push(("Arguments", self.field_specs()
+                                        .iter()
+                                        .map(|field| (String::from(field.name), tidy(field.description)))
+                                        .collect()));
+
+        sections.push(("Options", self.flag_specs()
+                                    .iter()
+                                    .map(|flag| {
+                                        let names: Vec<String> = flag.names
+                                                                    .iter()
+                                                                    .map(|name| match flag.value_name {
+                                                                        Some(value_name) => format!("{name}={value_name}"),
+                                                                        None => name.clone()
+                                                                    })
+                                                                    .collect();
+
+                                        (names.join(", "), tidy(flag.description))
+                                    })
+                                    .collect()));
+
+        let width = sections.iter().flat_map(|(_, items)| items).map(|(names, _)| names.chars().count()).max().unwrap_or_default();
+        let mut help = String::new();
+
+        let _ = writeln!(help, "usage: {}", format!("{self}").trim_end());
+
+        if let Some(about) = &self.about {
+            let _ = writeln!(help);
+            let _ = writeln!(help, "{}", about.trim_end());
+        }
+
+        for (title, items) in sections.iter().filter(|(_, items)| !items.is_empty()) {
+            let _ = writeln!(help);
+            let _ = writeln!(help, "{title}:");
+
+            for (names, description) in items {
+                let _ = writeln!(help, "  {}", format!("{names:width$}  {description}").trim_end());
+            }
+        }
+
+        help
+    }
+
+    /// Gives a subcommand's `Args` every global flag and option, and those
+    /// given before it on the command-line, if it was found at `found`.
+    fn inherit_globals(&self, args: &mut Args, found: Option<usize>) {
+        for flag in &self.globals {
+            let key = flag.split_once('=').map_or(flag.as_str(), |(names, _)| names);
+
+            for (name, description) in self.descriptions.iter().filter(|(name, _)| *name == key || flag_names(flag).contains(name)) {
+                args.descriptions.insert(name.clone(), description.clone());
+            }
+
+            if let Some(default) = self.defaults.get(key) {
+                args.defaults.insert(String::from(key), default.clone());
+            }
+
+            if let Some(choices) = self.choices.get(key) {
+                args.choices.insert(String::from(key), choices.clone());
+            }
+
+            if let Some(hint) = self.hints.get(key) {
+                args.hints.insert(String::from(key), *hint);
+            }
+
+            if self.sensitive.contains(key) {
+                args.sensitive.insert(String::from(key));
+            }
+
+            args.secret_files.extend(self.secret_files.iter().filter(|(secret, _)| secret == key).cloned());
+            args.push_possible_flag(flag.clone());
+        }
+
+        args.globals = self.globals.clone();
+
+        if let Some(i) = found {
+            // The name of a multi-call program is not on the command-line.
+            args.argv_offset = self.argv_offset + i + usize::from(!self.multi_call);
+            args.outer_globals = self.outer_globals
+                                    .iter()
+                                    .cloned()
+                                    .chain(self.own_flags().into_iter().filter(|(_, flag)| self.is_global(flag)))
+                                    .collect();
+        }
+
+        args.tokenize();
+    }
+
+    /// Gives every global flag found at this level of the command-line or
+    /// below, with its index in `argv`.
+    fn given_globals(&self) -> Vec<(usize, String)> {
+        self.own_flags()
+            .into_iter()
+            .filter(|(_, flag)| self.is_global(flag))
+            .chain(self.inner_globals.iter().cloned())
+            .collect()
+    }
+
+    /// Indicates whether a flag found on the command-line is global.
+    fn is_global(&self, flag: &str) -> bool {
+        let name = flag.split_once('=').map_or(flag, |(name, _)| name);
+
+        self.globals.iter().any(|global| flag_names(global).iter().any(|n| n == name))
+    }
+
+    /// Gives the names of every visible subcommand, with its summary,
+    /// followed by the `help` subcommand. Empty if there are no
+    /// subcommands.
+    pub(crate) fn subcommand_summaries(&self) -> Vec<(Vec<String>, String)> {
+        let mut summaries: Vec<(Vec<String>, String)> = self.subcommands
+                                                            .iter()
+                                                            .filter(|subcommand| !subcommand.hidden)
+                                                            .map(|subcommand| (subcommand.names.clone(), summary(subcommand.args.about.as_deref())))
+                                                            .collect();
+
+        if !summaries.is_empty() {
+            summaries.push((vec![String::from("help")], String::from("Show help for a command")));
+        }
+
+        summaries
+    }
+
+    /// Gives the subcommand with a name or alias, or `None` if there is
+    /// none.
+    pub(crate) fn find_subcommand(&self, name: &str) -> Option<&Subcommand> {
+        self.subcommands.iter().find(|subcommand| subcommand.names.iter().any(|n| n == name))
+    }
+
+    /// Gives a problem if subcommands are specified and none was given, or
+    /// for every problem with the one that was given.
+    pub(crate) fn subcommand_problems(&self) -> Vec<(String, Option<String>)> {
+        if self.subcommands.is_empty() && self.external.is_none() {
+            return Vec::new();
+        }
+
+        match (self.subcommand_at, self.get_subcommand()) {
+            (_, Some((_, args))) => args.problems(),
+            (Some(at), None) if self.tokens[at] == "help" || self.get_external_subcommand().is_some() => Vec::new(),
+            (Some(at), None) => vec![self.unknown_subcommand(&self.tokens[at])],
+            (None, _) => match self.command_line.first() {
+                Some(name) => vec![self.unknown_subcommand(name)],
+                None => {
+                    let names: Vec<String> = self.subcommands
+                                                .iter()
+                                                .filter(|subcommand| !subcommand.hidden)
+                                                .map(|subcommand| subcommand.names[0].clone())
+                                                .chain(self.external_subcommands())
+                                                .collect();
+
+                    match names.is_empty() {
+                        true => vec![(String::from("no command given"), None)],
+                        false => vec![(format!("no command given, expected one of: {}", names.join(", ")), None)]
+                    }
+                }
+            }
+        }
+    }
+
+    /// Gives the problem for an unknown subcommand, with the closest visible
+    /// or external subcommand as a suggestion.
+    fn unknown_subcommand(&self, name: &str) -> (String, Option<String>) {
+        let external = self.external_subcommands();
+        let names = self.subcommands
+                        .iter()
+                        .filter(|subcommand| !subcommand.hidden)
+                        .flat_map(|subcommand| subcommand.names.iter().map(String::as_str))
+                        .chain(external.iter().map(String::as_str));
+
+        (format!("unknown command '{name}'"), self.suggest(name, names))
+    }
+}
+
+/// Gives the first line of a description, which is its summary.
+fn summary(about: Option<&str>) -> String {
+    about.and_then(|about| about.lines().next()).unwrap_or_default().trim().to_string()
+}
+
+/// Gives a description on a single line.
+fn tidy(description: Option<&String>) -> String {
+    description.map(|description| description.split_whitespace().collect::<Vec<&str>>().join(" ")).unwrap_or_default()
+}
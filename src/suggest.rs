@@ -0,0 +1,134 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use crate::args::{flag_names, switch_names, Args, Given};
+
+
+impl Args {
+    /// Sets how similar an unknown command, flag or choice must be to a
+    /// known one for `check()` to suggest it, from `0.0` to `1.0`. The
+    /// default is `0.7`.
+    /// 
+    /// Similarity is one less the Damerau-Levenshtein distance between the
+    /// two, divided by the length of the longer one, so `1.0` turns
+    /// suggestions off.
+    pub fn suggestion_threshold(&mut self, similarity: f64) -> &mut Self {
+        self.suggestion_threshold = similarity;
+
+        self
+    }
+
+    /// Makes any flag given on the command-line that has not been specified
+    /// an error by `check()`, e.g. `unknown flag '--verbsoe', did you mean
+    /// '--verbose'?`.
+    pub fn reject_unknown_flags(&mut self) -> &mut Self {
+        self.reject_unknown = true;
+
+        self
+    }
+
+    /// Gives the candidate most similar to `word`, if it is at least as
+    /// similar as the suggestion threshold. The earliest candidate wins a
+    /// tie, and `word` itself is never suggested.
+    pub(crate) fn suggest<'a>(&self, word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+        if self.suggestion_threshold >= 1.0 {
+            return None;
+        }
+
+        let mut best: Option<(&str, f64)> = None;
+
+        for candidate in candidates.into_iter().filter(|candidate| *candidate != word) {
+            let longest = word.chars().count().max(candidate.chars().count());
+            let similarity = 1.0 - distance(word, candidate) as f64 / longest as f64;
+
+            if similarity >= self.suggestion_threshold && best.is_none_or(|(_, best)| similarity > best) {
+                best = Some((candidate, similarity));
+            }
+        }
+
+        best.map(|(candidate, _)| String::from(candidate))
+    }
+
+    /// Gives a problem for every flag on the command-line that has not been
+    /// specified, if unknown flags are rejected, with the closest specified
+    /// flag as a suggestion.
+    pub(crate) fn unknown_flag_problems(&self, given: &impl Given) -> Vec<(String, Option<String>)> {
+        if !self.reject_unknown {
+            return Vec::new();
+        }
+
+        let names: Vec<String> = self.possible_flags
+                                    .iter()
+                                    .flat_map(|flag| flag_names(flag))
+                                    .chain(self.switches.iter().flat_map(|(switch, _)| {
+                                        let (on, off) = switch_names(switch);
+                                        [on, off]
+                                    }))
+                                    .collect();
+
+        given.flags()
+            .into_iter()
+            .map(|flag| flag.split_once('=').map_or(flag, |(name, _)| name))
+            .filter(|name| !names.iter().any(|n| n == name))
+            .filter(|name| self.abbreviation_candidates(name).is_empty())
+            .filter(|name| self.subcommands.is_empty() || !matches!(*name, "--help" | "-h"))
+            .map(|name| (format!("unknown flag '{name}'"), self.suggest(name, names.iter().map(String::as_str))))
+            .collect()
+    }
+}
+
+
+/// Gives the optimal string alignment distance between two strings: the
+/// number of insertions, deletions, substitutions and transpositions of
+/// adjacent characters that turn one into the other.
+pub(crate) fn distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    // Rows for the previous two prefixes of `a`, and the current one.
+    let mut before: Vec<usize> = vec![0; b.len() + 1];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            current[j] = (previous[j] + 1).min(current[j - 1] + 1).min(previous[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+
+        std::mem::swap(&mut before, &mut previous);
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
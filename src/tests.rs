@@ -25,13 +25,77 @@
 ---------------------------------------------------------------------------- */
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::args::*;
+    use crate::completion::*;
+    use crate::provenance::*;
+    use std::io::{BufRead, Write};
 
     fn to_string_vec(array: Vec<&str>) -> Vec<String> {
         array.iter().map(|x| String::from(*x)).collect()
     }
 
+    /// Creates an empty directory for a single test, so that neither tests
+    /// nor concurrent runs share files.
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("args-helper-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    /// Runs `f` with environment variables set, one test at a time, so that
+    /// no test sees another's variables.
+    fn with_variables<T>(variables: &[(&str, &str)], f: impl FnOnce() -> T) -> T {
+        static ENVIRONMENT: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+        let _guard = ENVIRONMENT.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        for (variable, value) in variables {
+            std::env::set_var(variable, value);
+        }
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(f));
+
+        for (variable, _) in variables {
+            std::env::remove_var(variable);
+        }
+
+        result.unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    }
+
+    /// Parses `command_line` against the spec that `spec` declares.
+    fn parse(command_line: Vec<&str>, spec: impl FnOnce(&mut Args)) -> Args {
+        let mut args = Args::from(command_line);
+        spec(&mut args);
+
+        args
+    }
+
+    /// Small xorshift generator, so that the fuzz tests are repeatable.
+    struct Fuzz(u64);
+
+    impl Fuzz {
+        fn next(&mut self) -> usize {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 as usize
+        }
+
+        fn token(&mut self) -> String {
+            const PIECES: [&str; 16] = ["", "-", "+", "--", "a", "1", ".", "e", "=", " ", "é", "\0", "\u{1F600}", "-5", "+x", "inf"];
+
+            (0..self.next() % 4).map(|_| PIECES[self.next() % PIECES.len()]).collect()
+        }
+
+        fn tokens(&mut self) -> Vec<String> {
+            (0..self.next() % 8).map(|_| self.token()).collect()
+        }
+    }
+
     #[test]
     fn args_from_str() {
         let args = Args::from(vec!["abc", "def", "xyz"]);
@@ -197,6 +261,50 @@ mod tests {
         assert_eq!(args.get("three"), Some(String::from("xyz")));
     }
 
+    #[test]
+    fn args_variadic() {
+        let mut args = Args::from(vec!["abc", "def", "xyz"]);
+        args.required("one")
+            .variadic("rest");
+
+        assert_eq!(args.get("rest"), Some(String::from("def")));
+        assert_eq!(args.get_all("rest"), vec!["def", "xyz"]);
+        assert_eq!(format!("{}", args), "<one> [rest...] ");
+        assert_eq!(format!("{:?}", args), "Args { required: \"<one>\", variadic: \"[rest...]\" }");
+    }
+
+    #[test]
+    fn args_variadic_empty() {
+        let mut args = Args::from(vec!["abc"]);
+        args.required("one")
+            .variadic("rest");
+
+        assert!(args.get("rest").is_none());
+        assert!(args.get_all("rest").is_empty());
+        assert!(args.check().is_ok());
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_optional_after_variadic() {
+        let mut args = Args::from(vec!["abc"]);
+        args.variadic("rest")
+            .optional("one");
+    }
+
+    #[test]
+    fn args_describe() {
+        let mut args = Args::from(vec!["abc"]);
+        args.required("one")
+            .describe("one", "The first one")
+            .flag("-v")
+            .describe("-v", "Verbose");
+
+        assert_eq!(args.get_description("one"), Some(String::from("The first one")));
+        assert_eq!(args.get_description("-v"), Some(String::from("Verbose")));
+        assert_eq!(args.get_description("two"), None);
+    }
+
     #[test]
     fn args_get_arg_good_1() {
         let mut args = Args::from(vec!["abc"]);
@@ -322,6 +430,142 @@ mod tests {
         assert!(!args.has_flag("-a"));
     }
 
+    #[test]
+    fn args_negative_number_is_value() {
+        let mut args = Args::from(vec!["offset", "-5", "-v"]);
+        args.required("one")
+            .required("two");
+
+        assert_eq!(args.get("two"), Some(String::from("-5")));
+        assert!(args.has_flag("-v"));
+        assert!(!args.has_flag("-5"));
+    }
+
+    #[test]
+    fn args_negative_number_is_flag_when_specified() {
+        let mut args = Args::from(vec!["abc", "-1", "def"]);
+        args.flag("-1")
+            .required("one")
+            .required("two");
+
+        assert_eq!(args.get("two"), Some(String::from("def")));
+        assert!(args.has_flag("-1"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_numeric_flag_after_required() {
+        let mut args = Args::from(vec!["abc", "-1"]);
+        args.required("one")
+            .flag("-1");
+    }
+
+    #[test]
+    fn args_lone_dash_is_value() {
+        let mut args = Args::from(vec!["-", "-x"]);
+        args.required("input");
+
+        assert_eq!(args.get("input"), Some(String::from("-")));
+        assert!(args.has_flag("-x"));
+    }
+
+    #[test]
+    fn args_token_rules_disabled() {
+        let mut args = Args::from(vec!["-", "-5", "abc"]);
+        args.set_token_rules(TokenRules { numbers_are_values: false, dash_is_value: false });
+        args.required("one");
+
+        assert_eq!(args.get("one"), Some(String::from("abc")));
+        assert!(args.has_flag("-"));
+        assert!(args.has_flag("-5"));
+    }
+
+    #[test]
+    fn args_empty_string_is_value() {
+        let mut args = Args::from(vec!["", "-v", "abc"]);
+        args.required("filter")
+            .required("prefix");
+
+        assert_eq!(args.get("filter"), Some(String::from("")));
+        assert_eq!(args.get("prefix"), Some(String::from("abc")));
+        assert!(args.check().is_ok());
+    }
+
+    #[test]
+    fn args_fuzz_from_never_panics() {
+        let mut fuzz = Fuzz(0x2545f4914f6cdd1d);
+
+        for _ in 0..10_000 {
+            let tokens = fuzz.tokens();
+
+            let mut args = Args::from(tokens.clone());
+            args.flag("-1")
+                .required("one")
+                .optional("two")
+                .flag("-a")
+                .flag("+b");
+
+            let _ = args.check();
+            let _ = args.get("one");
+            let _ = args.has_flag("-a");
+            let _ = format!("{args} {args:?}");
+
+            let _ = Args::from(tokens.iter().map(String::as_str).collect::<Vec<&str>>());
+        }
+    }
+
+    #[test]
+    fn args_fuzz_every_token_is_flag_or_value() {
+        let mut fuzz = Fuzz(0x9e3779b97f4a7c15);
+
+        for _ in 0..10_000 {
+            let tokens = fuzz.tokens();
+
+            let mut args = Args::from(tokens.clone());
+            args.flag("-5");
+            for i in 0..tokens.len() {
+                args.optional(&i.to_string());
+            }
+
+            // By default, a lone `-` and a signed number are values, unless
+            // the number is a specified flag.
+            let is_number = |t: &str| t[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.') && t[1..].parse::<f64>().is_ok();
+            let is_flag = |t: &&String| t.starts_with(['-', '+']) && *t != "-" && (*t == "-5" || !is_number(t));
+
+            let values: Vec<String> = (0..tokens.len()).filter_map(|i| args.get(&i.to_string())).collect();
+            let expected: Vec<String> = tokens.iter().filter(|t| !is_flag(t)).cloned().collect();
+
+            assert_eq!(values, expected, "tokens: {tokens:?}");
+            assert!(tokens.iter().filter(is_flag).all(|t| args.has_flag(t)), "tokens: {tokens:?}");
+        }
+    }
+
+    #[test]
+    fn args_get_input_output_file() {
+        let path = temp_dir("get-input-output").join("file.txt");
+        let path = path.to_str().unwrap();
+
+        let mut args = Args::from(vec![path]);
+        args.required("file");
+
+        writeln!(args.get_output("file").unwrap(), "hello").unwrap();
+
+        let mut line = String::new();
+        args.get_input("file").unwrap().read_line(&mut line).unwrap();
+
+        assert_eq!(line, "hello\n");
+    }
+
+    #[test]
+    fn args_default_is_empty() {
+        let mut args = Args::default();
+        args.optional("name")
+            .default_value("name", "none");
+
+        assert_eq!(args.get_program_name(), None);
+        assert_eq!(args.get("name"), Some(String::from("none")));
+    }
+
     #[test]
     fn args_check_good_1() {
         let mut args = Args::from(vec!["abc", "def", "xyz"]);
@@ -473,6 +717,1487 @@ mod tests {
         assert_eq!(format!("{:?}", args), "Args { program_name: \"hello-world\", required: \"<one>\", optional: \"[two]\" }");
     }
 
+    #[test]
+    fn args_flag_aliases() {
+        let mut args = Args::from(vec!["abc", "--verbose"]);
+        args.flag("-v|--verbose");
+
+        assert!(args.has_flag("-v"));
+        assert!(args.has_flag("--verbose"));
+        assert_eq!(format!("{}", args), "[-v|--verbose] ");
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_flag_alias_twice() {
+        let mut args = Args::from(vec!["abc"]);
+        args.flag("-v|--verbose")
+            .flag("--verbose");
+    }
+
+    #[test]
+    fn args_option() {
+        let mut args = Args::from(vec!["abc", "--out=x.txt", "-o=y.txt", "-e="]);
+        args.required("one")
+            .option("-o|--out", "FILE");
+
+        assert_eq!(args.get_option("--out"), Some(String::from("y.txt")));
+        assert_eq!(args.get_option("-e"), Some(String::from("")));
+        assert_eq!(args.get_option("--in"), None);
+        assert_eq!(format!("{}", args), "<one> [-o|--out=FILE] ");
+    }
+
+    #[test]
+    fn args_usage() {
+        let mut args = Args::from(vec!["abc", "-5", "-v", "--out=x"]);
+        args.usage("prog <name> <file> [filter] [-v|--verbose] [--out=FILE] [-5]").unwrap();
+
+        assert_eq!(args.get("name"), Some(String::from("abc")));
+        assert_eq!(args.get("file"), None);
+        assert!(args.has_flag("--verbose"));
+        assert!(args.has_flag("-5"));
+        assert_eq!(args.get_option("--out"), Some(String::from("x")));
+        assert_eq!(format!("{}", args), "prog <name> <file> [filter] [-v|--verbose] [--out=FILE] [-5] ");
+    }
+
+    #[test]
+    fn args_usage_round_trip() {
+        let mut args = Args::from(vec!["abc"]);
+        args.usage("<one> [two] [rest...] [+x] [-o|--out=FILE]").unwrap();
+
+        let mut again = Args::from(vec!["abc"]);
+        again.usage(&format!("{}", args)).unwrap();
+
+        assert_eq!(format!("{}", args), format!("{}", again));
+    }
+
+    #[test]
+    fn args_usage_errors() {
+        let problems = |usage| Args::from(vec!["abc"]).usage(usage).err().map(|e| Vec::from(e.get_problems()));
+
+        assert_eq!(problems("prog <name"), Some(vec![String::from("unbalanced '<' at position 5")]));
+        assert_eq!(problems("prog name]"), Some(vec![
+            String::from("unexpected 'name', expected '<name>' or '[name]'"),
+            String::from("unbalanced ']' at position 9")
+        ]));
+        assert_eq!(problems("[a <b>]"), Some(vec![
+            String::from("unexpected '<' inside '[' at position 0"),
+            String::from("unexpected '>' inside '[' at position 0"),
+            String::from("ambiguous name 'a <b>'")
+        ]));
+        assert_eq!(problems("[a] <b>"), Some(vec![String::from("required argument 'b' after optional argument")]));
+        assert_eq!(problems("<a> [-v|--a] [-v]"), Some(vec![String::from("'-v' specified twice")]));
+        assert_eq!(problems("<a> [-v|]"), Some(vec![String::from("ambiguous flag '-v|'")]));
+        assert_eq!(problems("<a> -v"), Some(vec![String::from("flag '-v' must be written as '[-v]'")]));
+        assert_eq!(problems("[a...] [b]"), Some(vec![String::from("argument 'b' after variadic argument")]));
+        assert_eq!(problems("[a...] <b> [c]"), Some(vec![String::from("argument 'c' after variadic argument")]));
+        assert_eq!(problems("[a...] <b> <c>"), None);
+        assert_eq!(problems("prog (--json|--yaml)"), Some(vec![String::from("group '(--json|--yaml)' is not supported, see group()")]));
+        assert!(problems("prog [--[no-]color]").is_some());
+    }
+
+    #[test]
+    fn args_choices() {
+        let mut args = Args::from(vec!["b", "--format=xml"]);
+        args.required("one")
+            .choices("one", &["a", "b"])
+            .option("-f|--format", "FMT")
+            .choices("-f", &["json", "yaml"]);
+
+        assert_eq!(args.check().unwrap_err().get_problems(), ["argument '-f' has invalid value 'xml', expected one of: json, yaml"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_choices_not_specified() {
+        let mut args = Args::from(vec!["b"]);
+        args.choices("one", &["a", "b"]);
+    }
+
+    fn spec_args() -> Args {
+        let mut args = Args::from(vec!["a"]);
+        args.set_program_name(Some(String::from("/usr/bin/prog")));
+        args.required("name")
+            .describe("name", "Name: a or b")
+            .choices("name", &["a", "b"])
+            .required("file")
+            .hint("file", ValueHint::File)
+            .optional("dir")
+            .hint("dir", ValueHint::Directory)
+            .variadic("rest")
+            .flag("-v|--verbose")
+            .describe("-v|--verbose", "Say [much] more")
+            .flag("+b")
+            .option("-o|--out", "FILE")
+            .hint("--out", ValueHint::File)
+            .option("--format", "FMT")
+            .describe("--format", "Output format, it's json or yaml")
+            .choices("--format", &["json", "yaml"]);
+
+        args
+    }
+
+    #[test]
+    fn args_completion_bash() {
+        assert_eq!(spec_args().completion(Shell::Bash), include_str!("../testdata/completion.bash"));
+    }
+
+    #[test]
+    fn args_completion_zsh() {
+        assert_eq!(spec_args().completion(Shell::Zsh), include_str!("../testdata/completion.zsh"));
+    }
+
+    #[test]
+    fn args_completion_fish() {
+        assert_eq!(spec_args().completion(Shell::Fish), include_str!("../testdata/completion.fish"));
+    }
+
+    /// A spec whose choices need quoting in every shell.
+    fn quoting_args() -> Args {
+        let mut args = Args::from(Vec::<String>::new());
+        args.set_program_name(Some(String::from("prog")));
+        args.required("mode")
+            .choices("mode", &["fast", "a b", "it's", "say \"hi\"", "$HOME", "`id`", "$(id)"])
+            .option("--level", "LEVEL")
+            .choices("--level", &["low", "very high"]);
+
+        args
+    }
+
+    #[test]
+    fn args_completion_quoting() {
+        let args = quoting_args();
+
+        assert_eq!(args.completion(Shell::Bash), include_str!("../testdata/quoting.bash"));
+        assert_eq!(args.completion(Shell::Zsh), include_str!("../testdata/quoting.zsh"));
+        assert_eq!(args.completion(Shell::Fish), include_str!("../testdata/quoting.fish"));
+    }
+
+    #[test]
+    fn args_man_page() {
+        let mut args = spec_args();
+        args.about("Does things to files.\n\nReads a file, and does things to it.\n.Honestly.")
+            .environment("PROG_HOME", "Where things are kept.")
+            .exit_status(0, "Things were done.")
+            .exit_status(2, "Nothing was done.");
+
+        assert_eq!(args.man_page(), include_str!("../testdata/man.1"));
+    }
+
+    #[test]
+    fn args_man_page_default_exit_status() {
+        let mut args = Args::from(vec!["a"]);
+        args.set_program_name(Some(String::from("prog")));
+
+        assert_eq!(args.man_page(), ".TH \"PROG\" \"1\"\n.SH NAME\nprog\n.SH SYNOPSIS\n.B prog\n.SH EXIT STATUS\n.TP\n\\fB0\\fR\nSuccess.\n.TP\n\\fB>0\\fR\nAn error occurred.\n");
+    }
+
+    #[test]
+    fn args_markdown() {
+        let mut args = spec_args();
+        args.about("Does things to files.")
+            .environment("PROG_HOME", "Where things are kept.")
+            .exit_status(0, "Things were done.");
+
+        args.check_markdown("testdata/reference.md").unwrap();
+    }
+
+    #[test]
+    fn args_markdown_drift() {
+        let path = temp_dir("markdown-drift").join("reference.md");
+        let args = spec_args();
+
+        std::fs::write(&path, args.markdown().replace("[-v|--verbose]", "[-v]")).unwrap();
+        let error = args.check_markdown(&path).unwrap_err();
+
+        assert_eq!(error.get_problems(), [format!("'{}' does not match the spec at line 6: expected 'prog <name> <file> [dir] [rest...] [-v|--verbose] [+b] [-o|--out=FILE] [--format=FMT]'", path.display())]);
+    }
+
+    #[test]
+    fn args_default() {
+        let mut args = Args::from(vec!["abc"]);
+        args.required("one")
+            .default_value("one", "xyz")
+            .optional("two")
+            .default_value("two", "def")
+            .option("-o|--out", "FILE")
+            .default_value("--out", "out.txt");
+
+        assert_eq!(args.get("one"), Some(String::from("abc")));
+        assert_eq!(args.get("two"), Some(String::from("def")));
+        assert_eq!(args.get_option("-o"), Some(String::from("out.txt")));
+    }
+
+    #[test]
+    fn args_default_required_still_missing() {
+        let mut args = Args::from(Vec::<&str>::new());
+        args.required("one")
+            .default_value("one", "xyz");
+
+        assert_eq!(args.get("one"), None);
+        assert!(args.check().is_err());
+    }
+
+    #[test]
+    fn args_prompt_missing() {
+        let mut args = Args::from(vec!["abc"]);
+        args.required("one")
+            .required("colour")
+            .describe("colour", "Favourite colour")
+            .choices("colour", &["red", "blue"])
+            .required("size")
+            .default_value("size", "big")
+            .required("password")
+            .default_value("password", "letmein")
+            .sensitive("password");
+
+        let mut reader = "green\nblue\n\nhunter2\n".as_bytes();
+        let mut writer = Vec::new();
+        args.prompt_missing(&mut reader, &mut writer).unwrap();
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get("colour"), Some(String::from("blue")));
+        assert_eq!(args.get("size"), Some(String::from("big")));
+        assert_eq!(args.get("password"), Some(String::from("hunter2")));
+        assert_eq!(String::from_utf8(writer).unwrap(),
+                   "Favourite colour (red/blue): expected one of: red, blue\nFavourite colour (red/blue): size [big]: password [<redacted>]: \n");
+    }
+
+    #[test]
+    fn args_prompt_without_echo_control() {
+        let mut args = Args::from(Vec::<&str>::new());
+        args.required("user")
+            .required("password")
+            .sensitive("password");
+
+        let mut reader = "abc\nhunter2\n".as_bytes();
+        let mut writer = Vec::new();
+        args.prompt_with(&mut reader, &mut writer, |_| false).unwrap();
+
+        assert_eq!(args.get("user"), Some(String::from("abc")));
+        assert_eq!(args.check().unwrap_err().get_problems(), ["required argument 'password' not found"]);
+        assert_eq!(String::from_utf8(writer).unwrap(), "user: ");
+    }
+
+    #[test]
+    fn args_prompt_missing_runs_out() {
+        let mut args = Args::from(Vec::<&str>::new());
+        args.required("one")
+            .required("two");
+
+        let mut reader = "abc\n".as_bytes();
+        args.prompt_missing(&mut reader, &mut Vec::new()).unwrap();
+
+        assert_eq!(args.get("one"), Some(String::from("abc")));
+        assert_eq!(args.check().unwrap_err().get_problems(), ["required argument 'two' not found"]);
+    }
+
+    fn group_spec(kind: GroupKind) -> impl Fn(&mut Args) {
+        move |args| {
+            args.required("one")
+                .flag("-q|--quiet")
+                .flag("-j|--json")
+                .flag("--yaml")
+                .option("--text", "STYLE")
+                .group("format", kind, &["--json", "--yaml", "--text"]);
+        }
+    }
+
+    #[test]
+    fn args_group_display() {
+        assert_eq!(format!("{}", parse(vec!["abc"], group_spec(GroupKind::AtMostOne))), "<one> [-q|--quiet] [-j|--json|--yaml|--text=STYLE] ");
+        assert_eq!(format!("{}", parse(vec!["abc"], group_spec(GroupKind::ExactlyOne))), "<one> [-q|--quiet] (-j|--json|--yaml|--text=STYLE) ");
+    }
+
+    #[test]
+    fn args_group_at_most_one() {
+        assert!(parse(vec!["abc"], group_spec(GroupKind::AtMostOne)).check().is_ok());
+        assert!(parse(vec!["abc", "--yaml", "--yaml"], group_spec(GroupKind::AtMostOne)).check().is_ok());
+        assert_eq!(parse(vec!["abc", "-j", "-q", "--text=x"], group_spec(GroupKind::AtMostOne)).check().unwrap_err().get_problems(),
+                   ["only one of -j|--json, --yaml, --text can be given for 'format', found: -j, --text=x"]);
+    }
+
+    #[test]
+    fn args_group_exactly_one() {
+        assert!(parse(vec!["abc", "--json"], group_spec(GroupKind::ExactlyOne)).check().is_ok());
+        assert_eq!(parse(vec!["abc"], group_spec(GroupKind::ExactlyOne)).check().unwrap_err().get_problems(),
+                   ["one of -j|--json, --yaml, --text is required for 'format'"]);
+        assert!(parse(vec!["abc", "--json", "--yaml"], group_spec(GroupKind::ExactlyOne)).check().is_err());
+    }
+
+    #[test]
+    fn args_group_at_least_one() {
+        assert!(parse(vec!["abc", "--json", "--yaml"], group_spec(GroupKind::AtLeastOne)).check().is_ok());
+        assert_eq!(parse(vec!["abc", "-q"], group_spec(GroupKind::AtLeastOne)).check().unwrap_err().get_problems(),
+                   ["at least one of -j|--json, --yaml, --text is required for 'format'"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_group_not_specified() {
+        let mut args = Args::from(vec!["abc"]);
+        args.flag("--json")
+            .group("format", GroupKind::AtMostOne, &["--json", "--xml"]);
+    }
+
+    fn condition_spec(args: &mut Args) {
+        args.optional("target")
+            .flag("--all")
+            .option("--key", "FILE")
+            .option("--cert", "FILE")
+            .option("--mode", "MODE")
+            .default_value("--mode", "plain")
+            .option("--ca", "FILE")
+            .requires("--key", "--cert")
+            .required_if("--ca", "--mode", "tls")
+            .required_unless("target", "--all");
+    }
+
+    #[test]
+    fn args_conditions_met() {
+        assert!(parse(vec!["abc"], condition_spec).check().is_ok());
+        assert!(parse(vec!["--all"], condition_spec).check().is_ok());
+        assert!(parse(vec!["abc", "--key=k", "--cert=c"], condition_spec).check().is_ok());
+        assert!(parse(vec!["abc", "--mode=tls", "--ca=c"], condition_spec).check().is_ok());
+    }
+
+    #[test]
+    fn args_required_unless_required_field() {
+        let mut args = Args::from(vec!["--all"]);
+        args.required("target")
+            .flag("--all")
+            .required_unless("target", "--all");
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get("target"), None);
+
+        let mut args = Args::from(Vec::<String>::new());
+        args.required("target")
+            .flag("--all")
+            .required_unless("target", "--all");
+
+        assert_eq!(args.check().unwrap_err().get_problems(), ["required argument 'target' not found"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "no argument, flag or option '--cret' specified")]
+    fn args_condition_undeclared() {
+        let mut args = Args::from(vec!["--key=k"]);
+        args.option("--key", "FILE")
+            .option("--cert", "FILE")
+            .requires("--key", "--cret");
+    }
+
+    #[test]
+    fn args_conditions_layered() {
+        let mut args = Args::from(vec!["--key=k"]);
+        args.optional("target")
+            .flag("--all")
+            .option("--key", "FILE")
+            .option("--cert", "FILE")
+            .requires("--key", "--cert")
+            .required_unless("target", "--all")
+            .env("--cert", "ARGS_HELPER_TEST_CERT")
+            .config_str("target = abc\n", "tool.conf");
+
+        with_variables(&[("ARGS_HELPER_TEST_CERT", "c.pem")], || {
+            assert_eq!(args.get_option("--cert"), Some(String::from("c.pem")));
+            assert!(args.check().is_ok());
+        });
+
+        let mut args = Args::from(Vec::<&str>::new());
+        args.required("target")
+            .flag("--all")
+            .required_unless("target", "--all")
+            .config_str("target = abc\n", "tool.conf");
+
+        assert!(args.check().is_ok());
+    }
+
+    #[test]
+    fn args_conditions_unmet() {
+        assert_eq!(parse(vec!["--key=k", "--mode=tls"], condition_spec).check().unwrap_err().get_problems(), [
+            "'--key' was given, so '--cert' is required as well",
+            "'--ca' is required when '--mode' is 'tls'",
+            "'target' is required unless '--all' is given"
+        ]);
+    }
+
+    #[test]
+    fn args_switch() {
+        let mut args = Args::from(vec!["--color", "-x", "--no-color", "+x", "--no-pager"]);
+        args.switch("--color", None)
+            .switch("+x", Some(false))
+            .switch("--pager", Some(true))
+            .switch("--fancy", Some(true))
+            .switch("-y", None);
+
+        assert_eq!(args.get_bool("--color"), Some(false));
+        assert_eq!(args.get_bool("--no-color"), Some(false));
+        assert_eq!(args.get_bool("+x"), Some(true));
+        assert_eq!(args.get_bool("--pager"), Some(false));
+        assert_eq!(args.get_bool("--fancy"), Some(true));
+        assert_eq!(args.get_bool("-y"), None);
+        assert_eq!(format!("{}", args), "[--[no-]color] [+x|-x] [--[no-]pager] [--[no-]fancy] [+y|-y] ");
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_switch_twice() {
+        let mut args = Args::from(vec!["abc"]);
+        args.flag("--no-color")
+            .switch("--color", None);
+    }
+
+    #[test]
+    fn args_abbreviations() {
+        let mut args = Args::from(vec!["--verb", "--col", "--ver", "--all", "--q"]);
+        args.allow_abbreviations()
+            .flag("-v|--verbose")
+            .flag("--version")
+            .flag("--colour")
+            .flag("--all")
+            .flag("--all-files")
+            .switch("--quiet", None);
+
+        assert!(args.has_flag("-v"));
+        assert!(args.has_flag("--colour"));
+        assert!(args.has_flag("--all"));
+        assert!(!args.has_flag("--all-files"));
+        assert!(!args.has_flag("--version"));
+        assert_eq!(args.get_bool("--quiet"), None);
+
+        assert_eq!(args.check().unwrap_err().get_problems(), ["ambiguous flag '--ver', could be: --verbose, --version"]);
+    }
+
+    #[test]
+    fn args_abbreviations_off() {
+        let mut args = Args::from(vec!["--verb"]);
+        args.flag("--verbose");
+
+        assert!(!args.has_flag("--verbose"));
+        assert!(args.check().is_ok());
+    }
+
+    #[test]
+    fn args_config_precedence() {
+        let dir = temp_dir("config-precedence");
+        let system = dir.join("system.conf");
+        let user = dir.join("user.conf");
+        std::fs::write(&system, "# system\nname = system\nout = system.txt\nlevel = 1\ncolor = yes\n").unwrap();
+        std::fs::write(&user, "; user\nout = 'user.txt'\nlevel = \"2\"\n").unwrap();
+
+        let mut args = Args::from(vec!["--mode=cli"]);
+        args.required("name")
+            .optional("extra")
+            .option("-o|--out", "FILE")
+            .option("--level", "N")
+            .option("--mode", "MODE")
+            .option("--unset", "X")
+            .switch("--color", None)
+            .default_value("extra", "default")
+            .default_value("--mode", "default")
+            .default_value("--unset", "default")
+            .env("--level", "ARGS_HELPER_TEST_LEVEL")
+            .env("--mode", "ARGS_HELPER_TEST_MODE_UNSET")
+            .config_file(&system)
+            .config_file(&user)
+            .config_file(dir.join("missing.conf"));
+
+        with_variables(&[("ARGS_HELPER_TEST_LEVEL", "3")], || {
+            assert!(args.check().is_ok());
+            assert_eq!(args.get("name"), Some(String::from("system")));
+            assert_eq!(args.get("extra"), Some(String::from("default")));
+            assert_eq!(args.get_option("-o"), Some(String::from("user.txt")));
+            assert_eq!(args.get_option("--level"), Some(String::from("3")));
+            assert_eq!(args.get_option("--mode"), Some(String::from("cli")));
+            assert_eq!(args.get_option("--unset"), Some(String::from("default")));
+            assert_eq!(args.get_bool("--color"), Some(true));
+        });
+    }
+
+    #[test]
+    fn args_env_choices() {
+        let mut args = Args::from(Vec::<&str>::new());
+        args.required("format")
+            .choices("format", &["json", "yaml"])
+            .env("format", "ARGS_HELPER_TEST_FORMAT");
+
+        with_variables(&[("ARGS_HELPER_TEST_FORMAT", "yaml")], || assert!(args.check().is_ok()));
+        with_variables(&[("ARGS_HELPER_TEST_FORMAT", "xml")], || {
+            assert_eq!(args.check().unwrap_err().get_problems(), [
+                "argument 'format' has invalid value 'xml', expected one of: json, yaml"
+            ]);
+        });
+    }
+
+    #[test]
+    fn args_config_sections() {
+        let mut args = Args::from(Vec::<&str>::new());
+        args.option("--server-port", "PORT")
+            .option("--client-port", "PORT")
+            .option("--name", "NAME")
+            .config_str("[server]\nport = 80\n[ client ]\nport = 81\n[]\nname = x\n", "tool.conf");
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get_option("--server-port"), Some(String::from("80")));
+        assert_eq!(args.get_option("--client-port"), Some(String::from("81")));
+        assert_eq!(args.get_option("--name"), Some(String::from("x")));
+    }
+
+    #[test]
+    fn args_config_problems() {
+        let mut args = Args::from(vec!["abc"]);
+        args.required("name")
+            .option("--format", "FMT")
+            .switch("--color", None)
+            .choices("--format", &["json", "yaml"])
+            .config_str("[server]\nport = 80\n", "a.conf")
+            .config_str("verbose = true\ncolor = maybe\nformat = xml\nformat\n", "b.conf");
+
+        assert_eq!(args.check().unwrap_err().get_problems(), [
+            "unknown key 'server-port' in 'a.conf' at line 2",
+            "unknown key 'verbose' in 'b.conf' at line 1",
+            "'color' in 'b.conf' at line 2 has invalid value 'maybe', expected true or false",
+            "'format' in 'b.conf' at line 3 has invalid value 'xml', expected one of: json, yaml",
+            "expected 'key = value' in 'b.conf' at line 4"
+        ]);
+    }
+
+    #[test]
+    fn args_source_of() {
+        let mut args = Args::from(vec!["-v", "abc", "--no-color", "--level=2"]);
+        args.flag("-v|--verbose")
+            .required("name")
+            .optional("extra")
+            .option("-o|--out", "FILE")
+            .option("--level", "N")
+            .option("--mode", "MODE")
+            .switch("--color", Some(true))
+            .default_value("extra", "default")
+            .env("--out", "ARGS_HELPER_TEST_SOURCE_OUT")
+            .config_str("mode = fast\n", "tool.conf");
+
+        with_variables(&[("ARGS_HELPER_TEST_SOURCE_OUT", "env.txt")], || {
+            assert_eq!(args.source_of("name"), Some(Source::CommandLine(2)));
+            assert_eq!(args.source_of("extra"), Some(Source::Default));
+            assert_eq!(args.source_of("--verbose"), Some(Source::CommandLine(1)));
+            assert_eq!(args.source_of("--color"), Some(Source::CommandLine(3)));
+            assert_eq!(args.source_of("--level"), Some(Source::CommandLine(4)));
+            assert_eq!(args.source_of("-o"), Some(Source::Environment(String::from("ARGS_HELPER_TEST_SOURCE_OUT"))));
+            assert_eq!(args.source_of("--mode"), Some(Source::Config(String::from("tool.conf"), 1)));
+            assert_eq!(args.source_of("--other"), None);
+
+            assert_eq!(args.explain(), "\
+name = abc (command-line argument 2)
+extra = default (default)
+-v|--verbose = true (command-line argument 1)
+-o|--out = env.txt (environment variable 'ARGS_HELPER_TEST_SOURCE_OUT')
+--level = 2 (command-line argument 4)
+--mode = fast ('tool.conf' at line 1)
+--color|--no-color = false (command-line argument 3)
+");
+        });
+    }
+
+    #[test]
+    fn args_source_of_prompt() {
+        let mut args = Args::from(Vec::<&str>::new());
+        args.required("name")
+            .prompt_missing(&mut "abc\n".as_bytes(), &mut Vec::new())
+            .unwrap();
+
+        assert_eq!(args.source_of("name"), Some(Source::Prompt));
+        assert_eq!(args.explain(), "name = abc (prompt)\n");
+    }
+
+    #[test]
+    fn args_matches() {
+        let mut spec = Args::from(Vec::<&str>::new());
+        spec.allow_abbreviations()
+            .flag("-v|--verbose")
+            .option("-o|--out", "FILE")
+            .option("--level", "N")
+            .switch("--color", Some(true))
+            .required("name")
+            .optional("mode")
+            .variadic("rest")
+            .default_value("mode", "fast")
+            .default_value("--level", "1");
+
+        let line = vec!["abc", "--verb", "--out=a.txt", "slow", "x", "--no-color", "y", "-o=b.txt"];
+        let matches = spec.matches(&line);
+
+        assert!(matches.check().is_ok());
+        assert_eq!(matches.get("name"), Some("abc"));
+        assert_eq!(matches.get("mode"), Some("slow"));
+        assert_eq!(matches.get_all("rest"), ["x", "y"]);
+        assert!(matches.has_flag("-v"));
+        assert_eq!(matches.get_option("--out"), Some("b.txt"));
+        assert_eq!(matches.get_option("--level"), Some("1"));
+        assert_eq!(matches.get_bool("--color"), Some(false));
+
+        // Values borrow from the command-line, not from the matches.
+        let out = {
+            let matches = spec.matches(&line);
+            matches.get_option("--out")
+        };
+        assert_eq!(out, Some("b.txt"));
+
+        let line = vec!["-v"];
+        let matches = spec.matches(&line);
+
+        assert_eq!(matches.check().unwrap_err().get_problems(), ["required argument 'name' not found"]);
+        assert_eq!(matches.get("name"), None);
+        assert_eq!(matches.get("mode"), Some("fast"));
+        assert!(matches.get_all("rest").is_empty());
+        assert_eq!(matches.get_bool("--color"), Some(true));
+    }
+
+    #[test]
+    fn args_matches_problems() {
+        let mut spec = Args::from(Vec::<&str>::new());
+        spec.allow_abbreviations()
+            .reject_unknown_flags()
+            .flag("-q|--quiet")
+            .flag("-v|--verbose")
+            .flag("--version")
+            .option("--key", "FILE")
+            .option("--cert", "FILE")
+            .required("mode")
+            .choices("mode", &["fast", "slow"])
+            .group("output", GroupKind::AtMostOne, &["-q", "-v"])
+            .requires("--key", "--cert");
+
+        let problems = spec.matches(&["zzz", "-q", "-v", "--bogus"]).check().unwrap_err().get_problems().to_vec();
+        assert_eq!(problems, [
+            "argument 'mode' has invalid value 'zzz', expected one of: fast, slow",
+            "unknown flag '--bogus'",
+            "only one of -q|--quiet, -v|--verbose can be given for 'output', found: -q, -v"
+        ]);
+
+        let problems = spec.matches(&["--ver", "--key=k", "--quie"]).check().unwrap_err().get_problems().to_vec();
+        assert_eq!(problems, [
+            "required argument 'mode' not found",
+            "ambiguous flag '--ver', could be: --verbose, --version",
+            "'--key' was given, so '--cert' is required as well"
+        ]);
+
+        let matches = spec.matches(&["fast", "--quie", "--key=k", "--cert=c"]);
+        assert!(matches.check().is_ok());
+        assert!(matches.has_flag("-q"));
+        assert_eq!(matches.get_option("--cert"), Some("c"));
+    }
+
+    #[test]
+    fn args_required_after_variadic() {
+        let mut args = Args::from(vec!["a.txt", "-v", "b.txt", "c.txt", "dir"]);
+        args.flag("-v")
+            .variadic("sources")
+            .required("dest");
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get_all("sources"), ["a.txt", "b.txt", "c.txt"]);
+        assert_eq!(args.get("dest"), Some(String::from("dir")));
+        assert_eq!(format!("{}", args), "[sources...] <dest> [-v] ");
+
+        let spec = args;
+        let line = vec!["x", "y"];
+        let matches = spec.matches(&line);
+
+        assert_eq!(matches.get_all("sources"), ["x"]);
+        assert_eq!(matches.get("dest"), Some("y"));
+    }
+
+    #[test]
+    fn args_arity() {
+        let mut args = Args::from(vec!["a", "b", "c"]);
+        args.required("one")
+            .optional("two")
+            .optional("three")
+            .variadic("rest")
+            .required("last");
+
+        assert_eq!(args.get("one"), Some(String::from("a")));
+        assert_eq!(args.get("two"), Some(String::from("b")));
+        assert_eq!(args.get("three"), None);
+        assert!(args.get_all("rest").is_empty());
+        assert_eq!(args.get("last"), Some(String::from("c")));
+
+        let mut args = Args::from(vec!["a"]);
+        args.variadic("sources")
+            .required("dest")
+            .required("mode");
+
+        assert_eq!(args.get("dest"), Some(String::from("a")));
+        assert_eq!(args.check().unwrap_err().get_problems(), ["required argument 'mode' not found"]);
+    }
+
+    #[test]
+    fn args_arity_source_of() {
+        let mut args = Args::from(vec!["a", "b", "c"]);
+        args.variadic("sources")
+            .required("dest");
+
+        assert_eq!(args.source_of("sources"), Some(Source::CommandLine(1)));
+        assert_eq!(args.source_of("dest"), Some(Source::CommandLine(3)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_required_after_unfound_optional() {
+        let mut args = Args::from(vec!["abc"]);
+        args.optional("one")
+            .required("two");
+    }
+
+    fn subcommand_spec(args: &mut Args) {
+        args.set_program_name(Some(String::from("/usr/bin/tool")));
+        args.about("Builds and tests things.")
+            .flag("-v|--verbose")
+            .describe("-v|--verbose", "Say more")
+            .subcommand("build|b", |build| {
+                build.about("Build the project\nin the current directory.")
+                    .optional("target")
+                    .describe("target", "What to build")
+                    .flag("--release");
+            })
+            .subcommand("remote", |remote| {
+                remote.about("Manage remotes")
+                    .subcommand("add", |add| {
+                        add.about("Add a remote")
+                            .required("name")
+                            .required("url")
+                            .hint("url", ValueHint::File);
+                    });
+            })
+            .subcommand("debug", |debug| {
+                debug.flag("--dump");
+            })
+            .hide_subcommand("debug");
+    }
+
+    #[test]
+    fn args_subcommand() {
+        let args = parse(vec!["-v", "b", "--release", "all"], subcommand_spec);
+
+        assert!(args.check().is_ok());
+        assert!(args.has_flag("--verbose"));
+        assert!(!args.has_flag("--release"));
+        assert_eq!(args.help_requested(), None);
+
+        let (name, build) = args.get_subcommand().unwrap();
+        assert_eq!(name, "build");
+        assert!(build.has_flag("--release"));
+        assert_eq!(build.get("target"), Some(String::from("all")));
+        assert_eq!(build.get_program_name(), Some(String::from("tool build")));
+
+        let args = parse(vec!["remote", "add", "origin", "x.git"], subcommand_spec);
+        let (_, remote) = args.get_subcommand().unwrap();
+        let (_, add) = remote.get_subcommand().unwrap();
+        assert_eq!(add.get("url"), Some(String::from("x.git")));
+        assert_eq!(format!("{}", add), "tool remote add <name> <url> ");
+
+        let args = parse(vec!["debug", "--dump"], subcommand_spec);
+        assert_eq!(args.get_subcommand().map(|(name, _)| name), Some("debug"));
+    }
+
+    #[test]
+    fn args_subcommand_problems() {
+        let args = parse(vec!["-v"], subcommand_spec);
+        assert_eq!(args.check().unwrap_err().get_problems(), ["no command given, expected one of: build, remote"]);
+
+        let args = parse(vec!["bulid"], subcommand_spec);
+        assert_eq!(args.check().unwrap_err().get_problems(), ["unknown command 'bulid', did you mean 'build'?"]);
+
+        let args = parse(vec!["remote", "add", "origin"], subcommand_spec);
+        assert_eq!(args.check().unwrap_err().get_problems(), ["required argument 'url' not found"]);
+
+        let args = parse(vec!["help", "remote"], subcommand_spec);
+        assert!(args.check().is_ok());
+        assert_eq!(args.get_subcommand().map(|(name, _)| name), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_subcommand_twice() {
+        let mut args = Args::from(vec!["abc"]);
+        args.subcommand("build|b", |_| ())
+            .subcommand("bench|b", |_| ());
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_subcommand_after_field() {
+        let mut args = Args::from(vec!["abc"]);
+        args.required("one")
+            .subcommand("build", |_| ());
+    }
+
+    #[test]
+    fn args_subcommand_help() {
+        let help = "\
+usage: tool <command> [-v|--verbose]
+
+Builds and tests things.
+
+Commands:
+  build, b       Build the project
+  remote         Manage remotes
+  help           Show help for a command
+
+Options:
+  -v, --verbose  Say more
+";
+
+        assert_eq!(parse(vec!["help"], subcommand_spec).help_requested(), Some(String::from(help)));
+        assert_eq!(parse(vec!["--help"], subcommand_spec).help_requested(), Some(String::from(help)));
+        assert_eq!(parse(vec!["-v"], subcommand_spec).help(), help);
+
+        let help = "\
+usage: tool build [target] [--release]
+
+Build the project
+in the current directory.
+
+Arguments:
+  target     What to build
+
+Options:
+  --release
+";
+
+        assert_eq!(parse(vec!["help", "build"], subcommand_spec).help_requested(), Some(String::from(help)));
+        assert_eq!(parse(vec!["b", "--help"], subcommand_spec).help_requested(), Some(String::from(help)));
+        assert_eq!(parse(vec!["remote", "help", "add"], subcommand_spec).help_requested().map(|help| help.lines().next().map(String::from)),
+                   Some(Some(String::from("usage: tool remote add <name> <url>"))));
+    }
+
+    #[test]
+    fn args_subcommand_completion() {
+        let args = parse(Vec::new(), subcommand_spec);
+
+        assert_eq!(args.completion(Shell::Bash), include_str!("../testdata/subcommands.bash"));
+        assert_eq!(args.completion(Shell::Zsh), include_str!("../testdata/subcommands.zsh"));
+        assert_eq!(args.completion(Shell::Fish), include_str!("../testdata/subcommands.fish"));
+    }
+
+    #[test]
+    fn args_subcommand_docs() {
+        let args = parse(Vec::new(), subcommand_spec);
+
+        assert!(args.markdown().contains("## Commands\n\n- `build`, `b` - Build the project\n- `remote` - Manage remotes\n- `help` - Show help for a command\n"));
+        assert!(args.man_page().contains(".SH COMMANDS\n.TP\n\\fBbuild\\fR, \\fBb\\fR\nBuild the project\n"));
+        assert!(!args.man_page().contains("debug"));
+    }
+
+    fn global_spec(args: &mut Args) {
+        args.flag("-v|--verbose")
+            .describe("-v|--verbose", "Say more")
+            .option("--config", "FILE")
+            .default_value("--config", "tool.conf")
+            .flag("--local")
+            .global("--verbose")
+            .global("--config")
+            .subcommand("remote", |remote| {
+                remote.subcommand("add", |add| {
+                    add.required("name");
+                });
+            });
+    }
+
+    #[test]
+    fn args_global() {
+        for command_line in [vec!["--verbose", "remote", "add", "x"], vec!["remote", "add", "x", "-v"], vec!["remote", "-v", "add", "x"]] {
+            let args = parse(command_line, global_spec);
+            let (_, remote) = args.get_subcommand().unwrap();
+            let (_, add) = remote.get_subcommand().unwrap();
+
+            assert!(args.check().is_ok());
+            assert!(args.has_flag("--verbose"));
+            assert!(remote.has_flag("-v"));
+            assert!(add.has_flag("--verbose"));
+            assert_eq!(add.get("name"), Some(String::from("x")));
+        }
+
+        let args = parse(vec!["--config=a.conf", "--local", "remote", "add", "x", "--config=b.conf", "--local"], global_spec);
+        let (_, remote) = args.get_subcommand().unwrap();
+        let (_, add) = remote.get_subcommand().unwrap();
+
+        assert_eq!(args.get_option("--config"), Some(String::from("b.conf")));
+        assert_eq!(add.get_option("--config"), Some(String::from("b.conf")));
+        assert_eq!(args.source_of("--config"), Some(Source::CommandLine(6)));
+        assert_eq!(add.source_of("--config"), Some(Source::CommandLine(6)));
+        assert_eq!(add.source_of("name"), Some(Source::CommandLine(5)));
+        assert!(args.has_flag("--local"));
+        assert!(!remote.has_flag("--local"));
+
+        let args = parse(vec!["remote", "add", "x"], global_spec);
+        let (_, remote) = args.get_subcommand().unwrap();
+        let (_, add) = remote.get_subcommand().unwrap();
+
+        assert!(!add.has_flag("--verbose"));
+        assert_eq!(add.get_option("--config"), Some(String::from("tool.conf")));
+        assert_eq!(add.source_of("--config"), Some(Source::Default));
+        assert_eq!(format!("{}", add), "<name> [-v|--verbose] [--config=FILE] ");
+        assert_eq!(add.get_description("-v|--verbose"), Some(String::from("Say more")));
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_global_after_subcommand() {
+        let mut args = Args::from(vec!["abc"]);
+        args.flag("-v")
+            .subcommand("build", |_| ())
+            .global("-v");
+    }
+
+    #[test]
+    fn args_suggestions() {
+        let mut args = Args::from(vec!["--verbsoe", "--format=jsno", "--formt", "-x"]);
+        args.flag("-v|--verbose")
+            .switch("--color", None)
+            .option("-f|--format", "FMT")
+            .choices("-f", &["json", "yaml"])
+            .reject_unknown_flags();
+
+        let error = args.check().unwrap_err();
+        assert_eq!(error.get_problems(), [
+            "argument '-f' has invalid value 'jsno', expected one of: json, yaml, did you mean 'json'?",
+            "unknown flag '--verbsoe', did you mean '--verbose'?",
+            "unknown flag '--formt', did you mean '--format'?",
+            "unknown flag '-x'"
+        ]);
+        assert_eq!(error.get_suggestions(), [Some(String::from("json")), Some(String::from("--verbose")), Some(String::from("--format")), None]);
+
+        let args = parse(vec!["remote", "addd", "origin"], subcommand_spec);
+        assert_eq!(args.check().unwrap_err().get_problems(), ["unknown command 'addd', did you mean 'add'?"]);
+
+        // Hidden subcommands are never suggested.
+        let args = parse(vec!["debgu"], subcommand_spec);
+        assert_eq!(args.check().unwrap_err().get_problems(), ["unknown command 'debgu'"]);
+    }
+
+    #[test]
+    fn args_suggestion_choices() {
+        let mut args = Args::from(vec!["yamml"]);
+        args.required("format")
+            .choices("format", &["json", "yaml"]);
+
+        let error = args.check().unwrap_err();
+        assert_eq!(error.get_problems(), ["argument 'format' has invalid value 'yamml', expected one of: json, yaml, did you mean 'yaml'?"]);
+        assert_eq!(error.get_suggestions(), [Some(String::from("yaml"))]);
+    }
+
+    #[test]
+    fn args_suggestion_threshold() {
+        let mut args = Args::from(vec!["--vrbs"]);
+        args.flag("--verbose")
+            .reject_unknown_flags();
+
+        assert_eq!(args.check().unwrap_err().get_suggestions(), [None]);
+
+        args.suggestion_threshold(0.5);
+        assert_eq!(args.check().unwrap_err().get_suggestions(), [Some(String::from("--verbose"))]);
+
+        args.suggestion_threshold(1.0);
+        assert_eq!(args.check().unwrap_err().get_problems(), ["unknown flag '--vrbs'"]);
+    }
+
+    #[test]
+    fn args_unknown_flags_allowed() {
+        let mut args = Args::from(vec!["--verbsoe"]);
+        args.flag("--verbose");
+
+        assert!(args.check().is_ok());
+    }
+
+    #[test]
+    fn suggest_distance() {
+        use crate::suggest::distance;
+
+        assert_eq!(distance("", ""), 0);
+        assert_eq!(distance("build", ""), 5);
+        assert_eq!(distance("build", "build"), 0);
+        assert_eq!(distance("bulid", "build"), 1);
+        assert_eq!(distance("biuld", "build"), 1);
+        assert_eq!(distance("bild", "build"), 1);
+        assert_eq!(distance("buildd", "build"), 1);
+        assert_eq!(distance("ca", "abc"), 3);
+        assert_eq!(distance("kitten", "sitting"), 3);
+    }
+
+    /// Creates a directory of stub scripts for external subcommands, with
+    /// whether each can be run.
+    #[cfg(unix)]
+    fn external_dir(name: &str, stubs: &[(&str, bool)]) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = temp_dir(name);
+
+        for (stub, executable) in stubs {
+            let path = dir.join(stub);
+            std::fs::write(&path, format!("#!/bin/sh\necho {stub} \"$@\"\n")).unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(if *executable { 0o755 } else { 0o644 })).unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn args_external_subcommand() {
+        let first = external_dir("external-1", &[("tool-foo", true), ("tool-bar", false)]);
+        let second = external_dir("external-2", &[("tool-foo", true), ("tool-fetch", true), ("tool-build", true), ("other-qux", true)]);
+        let path = std::env::join_paths([&first, &second]).unwrap();
+
+        let mut args = Args::from(vec!["-v", "foo", "--all", "x"]);
+        args.set_program_name(Some(String::from("/usr/bin/tool")));
+        args.flag("-v")
+            .subcommand("build", |_| ())
+            .allow_external_subcommands_in(&path);
+
+        assert!(args.check().is_ok());
+        assert!(args.has_flag("-v"));
+        assert!(args.get_subcommand().is_none());
+
+        let (program, rest) = args.get_external_subcommand().unwrap();
+        assert_eq!(program, first.join("tool-foo"));
+        assert_eq!(rest, ["--all", "x"]);
+
+        let output = std::process::Command::new(&program).args(&rest).output().unwrap();
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "tool-foo --all x\n");
+
+        // Built-in subcommands win over external ones.
+        let mut args = Args::from(vec!["build"]);
+        args.set_program_name(Some(String::from("tool")));
+        args.allow_external_subcommands_in(&path)
+            .subcommand("build", |_| ());
+
+        assert_eq!(args.get_subcommand().map(|(name, _)| name), Some("build"));
+        assert!(args.get_external_subcommand().is_none());
+
+        let mut args = Args::from(vec!["bar"]);
+        args.set_program_name(Some(String::from("tool")));
+        args.subcommand("build", |_| ())
+            .allow_external_subcommands_in(&path);
+
+        assert!(args.get_external_subcommand().is_none());
+        assert_eq!(args.check().unwrap_err().get_problems(), ["unknown command 'bar'"]);
+
+        let mut args = Args::from(vec!["fecth"]);
+        args.set_program_name(Some(String::from("tool")));
+        args.allow_external_subcommands_in(&path);
+
+        assert_eq!(args.check().unwrap_err().get_problems(), ["unknown command 'fecth', did you mean 'fetch'?"]);
+
+        let mut args = Args::from(Vec::<String>::new());
+        args.set_program_name(Some(String::from("tool")));
+        args.allow_external_subcommands_in(&path);
+
+        assert_eq!(args.check().unwrap_err().get_problems(), ["no command given, expected one of: build, fetch, foo"]);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn args_external_subcommand_help() {
+        let dir = external_dir("external-help", &[("tool-foo", true), ("tool-remote-add", true)]);
+
+        let mut args = Args::from(vec!["remote", "add"]);
+        args.set_program_name(Some(String::from("tool")));
+        args.subcommand("remote", |remote| {
+                remote.about("Manage remotes")
+                    .allow_external_subcommands_in(&dir);
+            })
+            .allow_external_subcommands_in(&dir);
+
+        assert_eq!(args.help(), "\
+usage: tool <command>
+
+Commands:
+  remote  Manage remotes
+  help    Show help for a command
+
+External commands:
+  foo
+");
+
+        let (_, remote) = args.get_subcommand().unwrap();
+        assert_eq!(remote.get_external_subcommand(), Some((dir.join("tool-remote-add"), Vec::new())));
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn args_external_subcommand_windows() {
+        let dir = temp_dir("external-windows");
+        std::fs::write(dir.join("tool-foo.exe"), "").unwrap();
+        std::fs::write(dir.join("tool-bar.txt"), "").unwrap();
+
+        let mut args = Args::from(vec!["foo", "x"]);
+        args.set_program_name(Some(String::from("C:\\bin\\tool.exe")));
+        args.allow_external_subcommands_in(&dir);
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get_external_subcommand(), Some((dir.join("tool-foo.exe"), vec![String::from("x")])));
+        assert_eq!(args.external_subcommands(), ["foo"]);
+    }
+
+    #[test]
+    #[cfg(windows)]
+    fn args_multi_call_windows() {
+        let args = parse(vec!["-l", "src"], multi_call_spec("ls.exe"));
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get_subcommand().map(|(name, _)| name), Some("ls"));
+
+        let args = parse(vec!["ls"], multi_call_spec("busybox.exe"));
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get_subcommand().map(|(name, _)| name), Some("ls"));
+        assert_eq!(args.to_shell_string(), "busybox.exe ls");
+    }
+
+    fn multi_call_spec(program: &str) -> impl Fn(&mut Args) + '_ {
+        move |args| {
+            args.set_program_name(Some(String::from(program)));
+            args.flag("-v")
+                .global("-v")
+                .multi_call("busybox")
+                .subcommand("ls", |ls| {
+                    ls.flag("-l")
+                        .optional("dir");
+                })
+                .subcommand("cat", |cat| {
+                    cat.variadic("files");
+                });
+        }
+    }
+
+    #[test]
+    fn args_multi_call() {
+        let args = parse(vec!["-l", "cat"], multi_call_spec("/bin/ls"));
+        let (name, ls) = args.get_subcommand().unwrap();
+
+        assert!(args.check().is_ok());
+        assert_eq!(name, "ls");
+        assert!(ls.has_flag("-l"));
+        assert_eq!(ls.get("dir"), Some(String::from("cat")));
+        assert_eq!(ls.source_of("dir"), Some(Source::CommandLine(2)));
+        assert_eq!(format!("{ls}").trim_end(), "ls [dir] [-v] [-l]");
+
+        let args = parse(vec!["-v", "cat", "a", "b"], multi_call_spec("/bin/busybox"));
+        let (name, cat) = args.get_subcommand().unwrap();
+
+        assert!(args.check().is_ok());
+        assert_eq!(name, "cat");
+        assert!(cat.has_flag("-v"));
+        assert_eq!(cat.get_all("files"), ["a", "b"]);
+        assert_eq!(cat.source_of("files"), Some(Source::CommandLine(3)));
+
+        let args = parse(vec!["ls"], multi_call_spec("/bin/dir"));
+        assert!(args.get_subcommand().is_none());
+        assert_eq!(args.check().unwrap_err().get_problems(), ["unknown command 'dir'"]);
+    }
+
+    #[test]
+    fn args_multi_call_help() {
+        let args = parse(vec!["--help"], multi_call_spec("ls"));
+        assert_eq!(args.help_requested().unwrap().lines().next(), Some("usage: ls [dir] [-v] [-l]"));
+
+        let args = parse(vec!["--help"], multi_call_spec("busybox"));
+        assert_eq!(args.help_requested().unwrap().lines().next(), Some("usage: busybox <command> [-v]"));
+    }
+
+    #[test]
+    fn args_from_shell_str() {
+        let mut args = Args::from_shell_str(r#"build "my target" --out='a b' -v # a comment"#).unwrap();
+        args.required("command")
+            .required("target")
+            .flag("-v")
+            .option("--out", "FILE");
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get("target"), Some(String::from("my target")));
+        assert_eq!(args.get_option("--out"), Some(String::from("a b")));
+        assert!(args.has_flag("-v"));
+
+        let words = |text: &str| crate::shell::shell_words(text).unwrap();
+
+        assert_eq!(words(""), Vec::<String>::new());
+        assert_eq!(words("  a\tb\nc  "), ["a", "b", "c"]);
+        assert_eq!(words(r#"'' "" a''b"#), ["", "", "ab"]);
+        assert_eq!(words(r#"'a \ "b"' "a \\ \" \$ \x" a\ b \'"#), [r#"a \ "b""#, r#"a \ " $ \x"#, "a b", "'"]);
+        assert_eq!(words("a \\\n b\\\nc \"d\\\ne\""), ["a", "bc", "de"]);
+        assert_eq!(words("a#b # c\n# d\n'#e' f"), ["a#b", "#e", "f"]);
+        assert_eq!(words("$HOME *.rs ~"), ["$HOME", "*.rs", "~"]);
+    }
+
+    #[test]
+    fn args_from_shell_str_problems() {
+        let problems = |text: &str| Args::from_shell_str(text).unwrap_err().get_problems().to_vec();
+
+        assert_eq!(problems("a 'b c"), ["unterminated single quote at position 2"]);
+        assert_eq!(problems(r#"a "b\""#), ["unterminated double quote at position 2"]);
+        assert_eq!(problems(r#"a "b\"#), ["unterminated double quote at position 2"]);
+        assert_eq!(problems("a b\\"), ["unterminated escape at position 3"]);
+    }
+
+    #[test]
+    fn args_to_shell_string() {
+        let mut args = Args::from(vec!["--no-color", "my target", "--verb", "-o", "it's", "--out=a b", "$x", "a=b", ""]);
+        args.set_program_name(Some(String::from("/usr/bin/tool")));
+        args.flag("-v|--verbose")
+            .switch("--color", None)
+            .option("-o|--out", "FILE")
+            .variadic("targets")
+            .allow_abbreviations();
+
+        assert_eq!(args.to_shell_string(), r#"/usr/bin/tool 'my target' 'it'\''s' '$x' 'a=b' '' --verbose -o '--out=a b' --no-color"#);
+
+        let args = parse(vec!["b", "--release", "-v", "x"], subcommand_spec);
+        assert_eq!(args.to_shell_string(), "/usr/bin/tool build x --release -v");
+
+        let args = parse(vec!["help", "remote", "--all"], subcommand_spec);
+        assert_eq!(args.to_shell_string(), "/usr/bin/tool help remote --all");
+
+        let args = parse(vec!["-l", "-v", "a b"], multi_call_spec("/bin/ls"));
+        assert_eq!(args.to_shell_string(), "/bin/ls 'a b' -v -l");
+    }
+
+    #[test]
+    fn args_to_shell_string_round_trip() {
+        let spec = |args: &mut Args| {
+            args.set_token_rules(TokenRules { numbers_are_values: true, dash_is_value: true });
+            args.flag("-v|--verbose")
+                .switch("--color", None)
+                .option("-o|--out", "FILE")
+                .required("name")
+                .variadic("rest")
+                .allow_abbreviations();
+        };
+
+        let mut fuzz = Fuzz(0x2545f4914f6cdd1d);
+        let mut next = |n: usize| fuzz.next() % n;
+
+        let pieces = ["a", "B", "-", "--", "=", " ", "\t", "\n", "'", "\"", "\\", "$", "`", "#", "~", "*", "é", "!", "5", "-5"];
+        let flags = ["-v", "--verbose", "--verb", "--color", "--no-color", "-x", "--out="];
+
+        for _ in 0..500 {
+            let mut tokens = Vec::new();
+
+            for _ in 0..next(6) {
+                let mut token: String = (0..next(5)).map(|_| pieces[next(pieces.len())]).collect();
+
+                match next(3) {
+                    0 => token = String::from(flags[next(flags.len())]),
+                    1 if token.starts_with(['-', '+']) => token.insert(0, 'v'),
+                    _ => ()
+                }
+
+                if token == "--out=" {
+                    token.extend((0..next(4)).map(|_| pieces[next(pieces.len())]));
+                }
+
+                tokens.push(token);
+            }
+
+            let mut args = Args::from(tokens.clone());
+            spec(&mut args);
+
+            let line = args.to_shell_string();
+            let mut parsed = Args::from_shell_str(&line).unwrap_or_else(|e| panic!("{tokens:?} gave {line}: {:?}", e.get_problems()));
+            spec(&mut parsed);
+
+            assert_eq!(parsed.get("name"), args.get("name"), "{tokens:?} gave {line}");
+            assert_eq!(parsed.get_all("rest"), args.get_all("rest"), "{tokens:?} gave {line}");
+            assert_eq!(parsed.has_flag("-v"), args.has_flag("-v"), "{tokens:?} gave {line}");
+            assert_eq!(parsed.get_bool("--color"), args.get_bool("--color"), "{tokens:?} gave {line}");
+            assert_eq!(parsed.get_option("--out"), args.get_option("--out"), "{tokens:?} gave {line}");
+            assert_eq!(parsed.check().err().map(|e| e.get_problems().to_vec()), args.check().err().map(|e| e.get_problems().to_vec()));
+            assert_eq!(parsed.to_shell_string(), line);
+        }
+    }
+
+    #[test]
+    fn args_sensitive_redacted() {
+        let mut args = Args::from(vec!["hunter2", "--token=s3cret", "--mode=a b"]);
+        args.set_program_name(Some(String::from("tool")));
+        args.required("password")
+            .option("-t|--token", "TOKEN")
+            .option("--mode", "MODE")
+            .choices("password", &["hunter3"])
+            .choices("--token", &["s3cret"])
+            .sensitive("password")
+            .sensitive("--token")
+            .config_str("token = s3cre\n", "tool.conf");
+
+        assert_eq!(args.check().unwrap_err().get_problems(), [
+            "'token' in 'tool.conf' at line 1 has invalid value '<redacted>', expected one of: s3cret",
+            "argument 'password' has invalid value '<redacted>', expected one of: hunter3"
+        ]);
+        assert_eq!(args.to_shell_string(), "tool '<redacted>' '--token=<redacted>' '--mode=a b'");
+        assert_eq!(args.explain(), "\
+password = <redacted> (command-line argument 1)
+-t|--token = <redacted> (command-line argument 2)
+--mode = a b (command-line argument 3)
+");
+
+        let matches = args.matches(&["hunter2", "--token=s3cret", "--mode=x"]);
+        assert_eq!(format!("{matches:?}"), r#"Matches { values: ["<redacted>"], flags: ["--token=<redacted>", "--mode=x"] }"#);
+        assert!(!format!("{args:?}").contains("hunter2"));
+
+        let mut args = Args::from(vec!["--token=hunter2", "--password=swordfish"]);
+        args.option("--token", "TOKEN")
+            .option("--password", "PASSWORD")
+            .sensitive("--token")
+            .sensitive("--password")
+            .group("auth", GroupKind::AtMostOne, &["--token", "--password"]);
+
+        assert_eq!(args.check().unwrap_err().get_problems(), [
+            "only one of --token, --password can be given for 'auth', found: --token=<redacted>, --password=<redacted>"
+        ]);
+
+        // Global options stay sensitive in subcommands.
+        let mut args = Args::from(vec!["remote", "--token=s3cret"]);
+        args.set_program_name(Some(String::from("tool")));
+        args.option("--token", "TOKEN")
+            .choices("--token", &["s3cre"])
+            .sensitive("--token")
+            .global("--token")
+            .subcommand("remote", |_| ());
+
+        assert_eq!(args.to_shell_string(), "tool remote '--token=<redacted>'");
+        assert_eq!(args.check().unwrap_err().get_problems(), [
+            "argument '--token' has invalid value '<redacted>', expected one of: s3cre"
+        ]);
+
+        let (_, remote) = args.get_subcommand().unwrap();
+        assert_eq!(remote.explain(), "--token = <redacted> (command-line argument 2)\n");
+    }
+
+    #[test]
+    fn args_sensitive_file() {
+        let dir = temp_dir("sensitive-file");
+        let path = dir.join("token.txt");
+        std::fs::write(&path, "s3cret\r\n").unwrap();
+
+        let mut args = Args::from(vec![format!("--token-file={}", path.display())]);
+        args.set_program_name(Some(String::from("tool")));
+        args.option("-t|--token", "TOKEN")
+            .sensitive_file("--token", "--token-file");
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get_option("-t"), Some(String::from("s3cret")));
+        assert_eq!(args.source_of("--token"), Some(Source::File(path.display().to_string())));
+        assert_eq!(args.explain().lines().next(), Some(format!("-t|--token = <redacted> (file '{}')", path.display()).as_str()));
+        assert_eq!(format!("{}", Source::File(String::from("-"))), "stdin");
+        assert!(args.help().contains("--token-file=FILE        Read --token from FILE, or - for stdin"));
+
+        let mut args = Args::from(vec!["-t=x", "--token-file=-"]);
+        args.option("-t|--token", "TOKEN")
+            .sensitive_file("--token", "--token-file");
+
+        assert_eq!(args.check().unwrap_err().get_problems(), ["only one of '--token' and '--token-file' can be given"]);
+        assert_eq!(args.get_option("--token"), Some(String::from("x")));
+
+        let missing = dir.join("missing.txt");
+        let mut args = Args::from(vec![format!("--token-file={}", missing.display())]);
+        args.option("--token", "TOKEN")
+            .sensitive_file("--token", "--token-file");
+
+        assert!(args.check().unwrap_err().get_problems()[0].starts_with(&format!("cannot read '{}': ", missing.display())));
+        assert_eq!(args.get_option("--token"), None);
+    }
+
+    #[test]
+    fn args_sensitive_file_lazy() {
+        let dir = temp_dir("sensitive-file-lazy");
+        let path = dir.join("token.txt");
+        std::fs::write(&path, "s3cret\n").unwrap();
+
+        // The file is read when the value is needed, by subcommands too.
+        let mut args = Args::from(vec![format!("--token-file={}", path.display()), String::from("remote")]);
+        args.set_program_name(Some(String::from("tool")));
+        args.option("--token", "TOKEN")
+            .global("--token")
+            .sensitive_file("--token", "--token-file")
+            .subcommand("remote", |_| ());
+
+        assert!(args.check().is_ok());
+        assert_eq!(args.get_option("--token"), Some(String::from("s3cret")));
+
+        // Each file is read once.
+        std::fs::write(&path, "changed\n").unwrap();
+        assert_eq!(args.get_option("--token"), Some(String::from("s3cret")));
+        std::fs::write(&path, "s3cret\n").unwrap();
+
+        let (_, remote) = args.get_subcommand().unwrap();
+        assert_eq!(remote.get_option("--token"), Some(String::from("s3cret")));
+
+        let mut spec = Args::from(Vec::<&str>::new());
+        spec.option("--token", "TOKEN")
+            .sensitive_file("--token", "--token-file");
+
+        let line = format!("--token-file={}", path.display());
+        let matches = spec.matches(&[&line]);
+        assert!(matches.check().is_ok());
+        assert_eq!(matches.get_option("--token"), None);
+        assert_eq!(matches.get_secret("--token"), Some(String::from("s3cret")));
+        assert_eq!(spec.matches(&["--token=x"]).get_secret("--token"), Some(String::from("x")));
+
+        let missing = format!("--token-file={}", dir.join("missing.txt").display());
+        assert_eq!(spec.matches(&[&missing]).check().unwrap_err().get_problems().len(), 1);
+        assert_eq!(spec.matches(&["--token=x", &line]).check().unwrap_err().get_problems(), [
+            "only one of '--token' and '--token-file' can be given"
+        ]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn args_sensitive_file_field() {
+        let mut args = Args::from(vec!["x"]);
+        args.required("token")
+            .sensitive_file("token", "--token-file");
+    }
+
+    #[test]
+    fn args_macro() {
+        let mut args = Args::from(vec!["abc", "-d", "def", "xyz", "--verbose"]);
+        let parsed = crate::args!(args =>
+            required name "The name",
+            optional filter,
+            variadic rest,
+            flag -d "debug",
+            flag +b,
+            flag --verbose
+        ).unwrap();
+
+        assert_eq!(parsed.name, "abc");
+        assert_eq!(parsed.filter, Some(String::from("def")));
+        assert_eq!(parsed.rest, vec!["xyz"]);
+        assert!(parsed.d);
+        assert!(!parsed.b);
+        assert!(parsed.verbose);
+        assert_eq!(args.get_description("name"), Some(String::from("The name")));
+        assert_eq!(args.get_description("-d"), Some(String::from("debug")));
+    }
+
+    #[test]
+    fn args_macro_error() {
+        let mut args = Args::from(vec!["abc"]);
+        let parsed = crate::args!(args => required name, required file);
+
+        assert!(parsed.is_err());
+        assert_eq!(format!("{}", args), "<name> <file> ");
+    }
+
+    #[test]
+    fn args_macro_new() {
+        let parsed = crate::args!{ optional name, flag -d };
+
+        assert!(parsed.is_ok());
+    }
+
     #[test]
     fn argserror_new_str() {
         let error = ArgsError::from("hello, world!");
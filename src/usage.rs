@@ -0,0 +1,231 @@
+/* ----------------------------------------------------------------------------
+
+    MIT License
+
+    Copyright (c) 2024 MW
+
+    Permission is hereby granted, free of charge, to any person obtaining a
+    copy of this software and associated documentation files (the "Software"),
+    to deal in the Software without restriction, including without limitation
+    the rights to use, copy, modify, merge, publish, distribute, sublicense,
+    and/or sell copies of the Software, and to permit persons to whom the
+    Software is furnished to do so, subject to the following conditions:
+
+    The above copyright notice and this permission notice shall be included in
+    all copies or substantial portions of the Software.
+
+    THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+    IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+    FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+    AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+    LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+    FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+    DEALINGS IN THE SOFTWARE.
+
+---------------------------------------------------------------------------- */
+
+use std::collections::HashSet;
+
+use crate::args::{Args, ArgsError, ArgsResult};
+
+
+/// A single specifier read from a usage string.
+enum Spec {
+    ProgramName(String),
+    Required(String),
+    Optional(String),
+    Variadic(String),
+    Flag(String),
+    Option(String, String)
+}
+
+
+//---------------------------------------------------------------------------//
+
+
+impl Args {
+    /// Creates a new `Self` populated with the command-line arguments, and
+    /// specified by a usage string.
+    ///
+    /// See `usage()`.
+    pub fn from_usage(usage: &str) -> ArgsResult<Self> {
+        let mut me = Self::new();
+        me.usage(usage)?;
+
+        Ok(me)
+    }
+
+    /// Specifies fields, flags and options from a usage string, in the same
+    /// form as `Display` gives for them, e.g.
+    /// `prog <name> <file> [filter] [-v|--verbose] [--out=FILE]`.
+    ///
+    /// - `<name>` is a required field.
+    /// - `[name]` is an optional field.
+    /// - `[name...]` is a variadic field.
+    /// - `[-v]` or `[-v|--verbose]` is a flag.
+    /// - `[--out=FILE]` or `[-o|--out=FILE]` is an option.
+    /// - A leading bare word is the program name.
+    ///
+    /// Groups and switches are not read, so they must be specified with
+    /// `group()` and `switch()`. `(--json|--yaml)` and `[--[no-]color]` are
+    /// errors, and `[--json|--yaml]` is a single flag with two names, not a
+    /// group.
+    ///
+    /// Returns an error, without specifying anything, if the usage string
+    /// is unbalanced or ambiguous.
+    pub fn usage(&mut self, usage: &str) -> ArgsResult<&mut Self> {
+        let (flags, fields): (Vec<Spec>, Vec<Spec>) = parse_usage(usage)?
+                                                        .into_iter()
+                                                        .partition(|spec| !matches!(spec, Spec::Required(_) | Spec::Optional(_) | Spec::Variadic(_)));
+
+        // Flags go first, so that numeric flags are not taken as values.
+        for spec in flags.into_iter().chain(fields) {
+            match spec {
+                Spec::ProgramName(name) => self.set_program_name(Some(name)),
+                Spec::Required(name) => {self.required(&name);},
+                Spec::Optional(name) => {self.optional(&name);},
+                Spec::Variadic(name) => {self.variadic(&name);},
+                Spec::Flag(name) => {self.flag(&name);},
+                Spec::Option(name, value_name) => {self.option(&name, &value_name);}
+            }
+        }
+
+        Ok(self)
+    }
+}
+
+
+/// Splits a usage string into specifiers, checking that they could be
+/// specified without panicking.
+fn parse_usage(usage: &str) -> ArgsResult<Vec<Spec>> {
+    let mut specs = Vec::new();
+    let mut problems = Vec::new();
+    let mut chars = usage.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let close = match c {
+            '<' => '>',
+            '[' => ']',
+            '>' | ']' => {
+                problems.push(format!("unbalanced '{c}' at position {start}"));
+                continue;
+            },
+            c if c.is_whitespace() => continue,
+            _ => ' '
+        };
+
+        let mut item = String::new();
+        if close == ' ' {
+            item.push(c);
+        }
+
+        let mut closed = close == ' ';
+        while let Some(&(_, c)) = chars.peek() {
+            if close == ' ' && (c.is_whitespace() || "<>[]".contains(c)) {
+                break;
+            }
+
+            chars.next();
+
+            if c == close {
+                closed = true;
+                break;
+            }
+
+            if "<>[]".contains(c) {
+                problems.push(format!("unexpected '{c}' inside '{}' at position {start}", &usage[start..=start]));
+            }
+
+            item.push(c);
+        }
+
+        if !closed {
+            problems.push(format!("unbalanced '{c}' at position {start}"));
+            continue;
+        }
+
+        match parse_item(c, item.trim(), specs.is_empty()) {
+            Ok(spec) => specs.push(spec),
+            Err(problem) => problems.push(problem)
+        }
+    }
+
+    check_specs(&specs, &mut problems);
+
+    if problems.is_empty() {
+        Ok(specs)
+    }
+    else {
+        Err(ArgsError::from(&problems))
+    }
+}
+
+/// Reads a single specifier, given the character that opened it.
+fn parse_item(open: char, item: &str, is_first: bool) -> Result<Spec, String> {
+    if item.is_empty() || item.contains(char::is_whitespace) {
+        return Err(format!("ambiguous name '{item}'"));
+    }
+
+    match open {
+        '<' if item.starts_with(['-', '+']) => Err(format!("required flag '{item}' is not supported")),
+        '<' => Ok(Spec::Required(String::from(item))),
+        '[' if item.starts_with(['-', '+']) => {
+            let (names, value_name) = match item.split_once('=') {
+                Some((names, value_name)) => (names, Some(value_name)),
+                None => (item, None)
+            };
+
+            if names.split('|').any(|name| name.len() < 2 || !name.starts_with(['-', '+'])) {
+                return Err(format!("ambiguous flag '{item}'"));
+            }
+
+            match value_name {
+                Some("") => Err(format!("option '{names}' has no value name")),
+                Some(value_name) => Ok(Spec::Option(String::from(names), String::from(value_name))),
+                None => Ok(Spec::Flag(String::from(names)))
+            }
+        },
+        '[' => match item.strip_suffix("...") {
+            Some("") => Err(format!("ambiguous name '{item}'")),
+            Some(name) => Ok(Spec::Variadic(String::from(name))),
+            None => Ok(Spec::Optional(String::from(item)))
+        },
+        _ if item.starts_with('(') => Err(format!("group '{item}' is not supported, see group()")),
+        _ if item.starts_with(['-', '+']) => Err(format!("flag '{item}' must be written as '[{item}]'")),
+        _ if is_first => Ok(Spec::ProgramName(String::from(item))),
+        _ => Err(format!("unexpected '{item}', expected '<{item}>' or '[{item}]'"))
+    }
+}
+
+/// Checks for problems that would make the `Args` specifiers panic.
+fn check_specs(specs: &[Spec], problems: &mut Vec<String>) {
+    let mut names = HashSet::new();
+    let mut seen_optional = false;
+    let mut seen_variadic = false;
+
+    for spec in specs {
+        let spec_names = match spec {
+            Spec::ProgramName(_) => continue,
+            Spec::Required(name) | Spec::Optional(name) | Spec::Variadic(name) => {
+                if seen_variadic && !matches!(spec, Spec::Required(_)) {
+                    problems.push(format!("argument '{name}' after variadic argument"));
+                }
+                else if seen_optional && !seen_variadic && matches!(spec, Spec::Required(_)) {
+                    problems.push(format!("required argument '{name}' after optional argument"));
+                }
+
+                seen_optional |= matches!(spec, Spec::Optional(_));
+                seen_variadic |= matches!(spec, Spec::Variadic(_));
+
+                vec![name.as_str()]
+            },
+            Spec::Flag(flags) | Spec::Option(flags, _) => flags.split('|').collect()
+        };
+
+        for name in spec_names {
+            if !names.insert(name) {
+                problems.push(format!("'{name}' specified twice"));
+            }
+        }
+    }
+}
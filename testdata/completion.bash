@@ -0,0 +1,41 @@
+# bash completion for prog
+
+_prog() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local cur="${line##*[[:space:]]}"
+    local words word n=0
+    read -ra words <<< "${line%"$cur"}"
+    for word in "${words[@]:${1:-1}}"; do
+        [[ "$word" == [-+]?* ]] || n=$((n + 1))
+    done
+
+    case "$cur" in
+        -o=*|--out=*)
+            COMPREPLY=($(compgen -f -- "${cur#*=}"))
+            return
+            ;;
+        --format=*)
+            COMPREPLY=($(compgen -W "json yaml" -- "${cur#*=}"))
+            return
+            ;;
+        [-+]*)
+            COMPREPLY=($(compgen -W "-v --verbose +b -o= --out= --format=" -- "$cur"))
+            [[ "${COMPREPLY[0]}" == *= ]] && compopt -o nospace
+            return
+            ;;
+    esac
+
+    case "$n" in
+        0)
+            COMPREPLY=($(compgen -W "a b" -- "$cur"))
+            ;;
+        1)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            ;;
+        2)
+            COMPREPLY=($(compgen -d -- "$cur"))
+            ;;
+    esac
+}
+
+complete -F _prog prog
@@ -0,0 +1,9 @@
+# fish completion for prog
+
+complete -c prog -s 'v' -l 'verbose' -d 'Say [much] more'
+complete -c prog -a '+b'
+complete -c prog -s 'o' -l 'out' -r -F
+complete -c prog -l 'format' -x -a 'json yaml' -d 'Output format, it\'s json or yaml'
+complete -c prog -n 'test (count (string match -rv -- "^[-+]." (commandline -opc))) -eq 1' -f -a 'a b' -d 'Name: a or b'
+complete -c prog -n 'test (count (string match -rv -- "^[-+]." (commandline -opc))) -eq 2' -F -d 'file'
+complete -c prog -n 'test (count (string match -rv -- "^[-+]." (commandline -opc))) -eq 3' -f -a '(__fish_complete_directories)' -d 'dir'
@@ -0,0 +1,31 @@
+# bash completion for prog
+
+_prog() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local cur="${line##*[[:space:]]}"
+    local words word n=0
+    read -ra words <<< "${line%"$cur"}"
+    for word in "${words[@]:${1:-1}}"; do
+        [[ "$word" == [-+]?* ]] || n=$((n + 1))
+    done
+
+    case "$cur" in
+        --level=*)
+            COMPREPLY=(); for choice in low 'very high'; do [[ $choice == "${cur#*=}"* ]] && COMPREPLY+=("$choice"); done
+            return
+            ;;
+        [-+]*)
+            COMPREPLY=($(compgen -W "--level=" -- "$cur"))
+            [[ "${COMPREPLY[0]}" == *= ]] && compopt -o nospace
+            return
+            ;;
+    esac
+
+    case "$n" in
+        0)
+            COMPREPLY=(); for choice in fast 'a b' 'it'\''s' 'say "hi"' '$HOME' '`id`' '$(id)'; do [[ $choice == "$cur"* ]] && COMPREPLY+=("$choice"); done
+            ;;
+    esac
+}
+
+complete -F _prog prog
@@ -0,0 +1,4 @@
+# fish completion for prog
+
+complete -c prog -l 'level' -x -a 'low \'very high\''
+complete -c prog -n 'test (count (string match -rv -- "^[-+]." (commandline -opc))) -eq 1' -f -a 'fast \'a b\' \'it\\\'s\' \'say "hi"\' \'$HOME\' \'`id`\' \'$(id)\'' -d 'mode'
@@ -0,0 +1,141 @@
+# bash completion for tool
+
+_tool() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local cur="${line##*[[:space:]]}"
+    local words word i=${1:-1} n=0
+    read -ra words <<< "${line%"$cur"}"
+    for word in "${words[@]:i}"; do
+        i=$((i + 1))
+        [[ "$word" == [-+]?* ]] && continue
+        case "$word" in
+            build|b)
+                _tool_build "$i"
+                return
+                ;;
+            remote)
+                _tool_remote "$i"
+                return
+                ;;
+            debug)
+                _tool_debug "$i"
+                return
+                ;;
+        esac
+        n=$((n + 1))
+    done
+
+    case "$cur" in
+        [-+]*)
+            COMPREPLY=($(compgen -W "-v --verbose" -- "$cur"))
+            [[ "${COMPREPLY[0]}" == *= ]] && compopt -o nospace
+            return
+            ;;
+    esac
+
+    case "$n" in
+        0)
+            COMPREPLY=($(compgen -W "build b remote help" -- "$cur"))
+            ;;
+    esac
+}
+
+_tool_build() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local cur="${line##*[[:space:]]}"
+    local words word n=0
+    read -ra words <<< "${line%"$cur"}"
+    for word in "${words[@]:${1:-1}}"; do
+        [[ "$word" == [-+]?* ]] || n=$((n + 1))
+    done
+
+    case "$cur" in
+        [-+]*)
+            COMPREPLY=($(compgen -W "--release" -- "$cur"))
+            [[ "${COMPREPLY[0]}" == *= ]] && compopt -o nospace
+            return
+            ;;
+    esac
+
+    case "$n" in
+    esac
+}
+
+_tool_remote() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local cur="${line##*[[:space:]]}"
+    local words word i=${1:-1} n=0
+    read -ra words <<< "${line%"$cur"}"
+    for word in "${words[@]:i}"; do
+        i=$((i + 1))
+        [[ "$word" == [-+]?* ]] && continue
+        case "$word" in
+            add)
+                _tool_remote_add "$i"
+                return
+                ;;
+        esac
+        n=$((n + 1))
+    done
+
+    case "$cur" in
+        [-+]*)
+            COMPREPLY=($(compgen -W "" -- "$cur"))
+            [[ "${COMPREPLY[0]}" == *= ]] && compopt -o nospace
+            return
+            ;;
+    esac
+
+    case "$n" in
+        0)
+            COMPREPLY=($(compgen -W "add help" -- "$cur"))
+            ;;
+    esac
+}
+
+_tool_remote_add() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local cur="${line##*[[:space:]]}"
+    local words word n=0
+    read -ra words <<< "${line%"$cur"}"
+    for word in "${words[@]:${1:-1}}"; do
+        [[ "$word" == [-+]?* ]] || n=$((n + 1))
+    done
+
+    case "$cur" in
+        [-+]*)
+            COMPREPLY=($(compgen -W "" -- "$cur"))
+            [[ "${COMPREPLY[0]}" == *= ]] && compopt -o nospace
+            return
+            ;;
+    esac
+
+    case "$n" in
+        1)
+            COMPREPLY=($(compgen -f -- "$cur"))
+            ;;
+    esac
+}
+
+_tool_debug() {
+    local line="${COMP_LINE:0:COMP_POINT}"
+    local cur="${line##*[[:space:]]}"
+    local words word n=0
+    read -ra words <<< "${line%"$cur"}"
+    for word in "${words[@]:${1:-1}}"; do
+        [[ "$word" == [-+]?* ]] || n=$((n + 1))
+    done
+
+    case "$cur" in
+        [-+]*)
+            COMPREPLY=($(compgen -W "--dump" -- "$cur"))
+            [[ "${COMPREPLY[0]}" == *= ]] && compopt -o nospace
+            return
+            ;;
+    esac
+
+    case "$n" in
+    esac
+}
+
+complete -F _tool tool
@@ -0,0 +1,12 @@
+# fish completion for tool
+
+complete -c tool -s 'v' -l 'verbose' -d 'Say more'
+complete -c tool -n '__fish_use_subcommand' -f -a 'build' -d 'Build the project'
+complete -c tool -n '__fish_use_subcommand' -f -a 'b' -d 'Build the project'
+complete -c tool -n '__fish_use_subcommand' -f -a 'remote' -d 'Manage remotes'
+complete -c tool -n '__fish_use_subcommand' -f -a 'help' -d 'Show help for a command'
+complete -c tool -n '__fish_seen_subcommand_from build b' -l 'release'
+complete -c tool -n '__fish_seen_subcommand_from remote; and not __fish_seen_subcommand_from add help' -f -a 'add' -d 'Add a remote'
+complete -c tool -n '__fish_seen_subcommand_from remote; and not __fish_seen_subcommand_from add help' -f -a 'help' -d 'Show help for a command'
+complete -c tool -n '__fish_seen_subcommand_from remote; and __fish_seen_subcommand_from add; and test (count (string match -rv -- "^[-+]." (commandline -opc))) -eq 4' -F -d 'url'
+complete -c tool -n '__fish_seen_subcommand_from debug' -l 'dump'